@@ -0,0 +1,62 @@
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+// HUNG_UP is the error for a peer that has gone away, usually because it gave up on the exchange.
+pub const HUNG_UP: &str = "peer hung up";
+
+// Endpoint is one end of a two-way channel between parties running in their own threads.
+pub struct Endpoint<M> {
+  tx: Sender<M>,
+  rx: Receiver<M>,
+}
+
+// pair connects two endpoints to each other.
+pub fn pair<M>() -> (Endpoint<M>, Endpoint<M>) {
+  let (atx, brx) = channel();
+  let (btx, arx) = channel();
+
+  (Endpoint { tx: atx, rx: arx }, Endpoint { tx: btx, rx: brx })
+}
+
+impl<M> Endpoint<M> {
+  pub fn send(&self, message: M) -> Result<(), &'static str> {
+    self.tx.send(message).map_err(|_| HUNG_UP)
+  }
+
+  pub fn recv(&self) -> Result<M, &'static str> {
+    self.rx.recv().map_err(|_| HUNG_UP)
+  }
+}
+
+// Side says which party sent a message.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Side {
+  A,
+  B,
+}
+
+// Mitm sits on the wire between A and B, and decides what each message is replaced with on the
+// way.
+pub trait Mitm<M> {
+  fn intercept(&mut self, from: Side, message: M) -> M;
+}
+
+impl<M, F: FnMut(Side, M) -> M> Mitm<M> for F {
+  fn intercept(&mut self, from: Side, message: M) -> M {
+    self(from, message)
+  }
+}
+
+// relay passes messages between the endpoints facing A and B through mitm, for protocols where the
+// two take turns and A speaks first. It returns once either side hangs up.
+pub fn relay<M>(a: Endpoint<M>, b: Endpoint<M>, mitm: &mut dyn Mitm<M>) {
+  loop {
+    let sent = a
+      .recv()
+      .and_then(|m| b.send(mitm.intercept(Side::A, m)))
+      .and_then(|_| b.recv())
+      .and_then(|m| a.send(mitm.intercept(Side::B, m)));
+    if sent.is_err() {
+      return;
+    }
+  }
+}
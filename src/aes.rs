@@ -7,7 +7,16 @@ pub trait Encrypter {
 }
 
 pub trait Decrypter {
-  fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, &str>;
+  fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, Error>;
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+  // Padding means the plaintext did not end in valid padding.
+  Padding,
+  // Rejected carries a plaintext that decrypted cleanly but that the receiver refused, as a
+  // careless receiver might echo it back in an error message.
+  Rejected(Vec<u8>),
 }
 
 pub struct ECB {
@@ -27,8 +36,8 @@ impl Encrypter for ECB {
   fn encrypt(&self, plain: &[u8]) -> Vec<u8> {
     let mut bb = pad(plain, 16);
     for i in 0..(bb.len() / 16) {
-      let mut block = aes::Block::from_mut_slice(&mut bb[i * 16..(i + 1) * 16]);
-      self.key.encrypt_block(&mut block);
+      let block = aes::Block::from_mut_slice(&mut bb[i * 16..(i + 1) * 16]);
+      self.key.encrypt_block(block);
     }
 
     bb
@@ -36,14 +45,14 @@ impl Encrypter for ECB {
 }
 
 impl Decrypter for ECB {
-  fn decrypt(&self, enc: &[u8]) -> Result<Vec<u8>, &str> {
+  fn decrypt(&self, enc: &[u8]) -> Result<Vec<u8>, Error> {
     let mut bb = Vec::from(enc);
     for i in 0..(bb.len() / 16) {
-      let mut block = aes::Block::from_mut_slice(&mut bb[i * 16..(i + 1) * 16]);
-      self.key.decrypt_block(&mut block);
+      let block = aes::Block::from_mut_slice(&mut bb[i * 16..(i + 1) * 16]);
+      self.key.decrypt_block(block);
     }
 
-    unpad(bb)
+    unpad(bb).map_err(|_| Error::Padding)
   }
 }
 
@@ -53,7 +62,7 @@ pub struct CBC {
 }
 
 impl CBC {
-  pub fn new<'a>(k: &[u8], iv: &[u8]) -> Result<CBC, &'static str> {
+  pub fn new(k: &[u8], iv: &[u8]) -> Result<CBC, &'static str> {
     if iv.len() != 16 {
       return Err("iv must be 16 bytes long");
     }
@@ -80,8 +89,8 @@ impl Encrypter for CBC {
         }
       }
 
-      let mut block = aes::Block::from_mut_slice(&mut bb[i * 16..(i + 1) * 16]);
-      self.key.encrypt_block(&mut block);
+      let block = aes::Block::from_mut_slice(&mut bb[i * 16..(i + 1) * 16]);
+      self.key.encrypt_block(block);
     }
 
     bb
@@ -89,13 +98,13 @@ impl Encrypter for CBC {
 }
 
 impl Decrypter for CBC {
-  fn decrypt(&self, enc: &[u8]) -> Result<Vec<u8>, &str> {
+  fn decrypt(&self, enc: &[u8]) -> Result<Vec<u8>, Error> {
     let mut bb = Vec::from(enc);
     let n = bb.len() / 16;
     for i in 0..n {
-      let mut block = aes::Block::from_mut_slice(&mut bb[(n - 1 - i) * 16..(n - i) * 16]);
+      let block = aes::Block::from_mut_slice(&mut bb[(n - 1 - i) * 16..(n - i) * 16]);
 
-      self.key.decrypt_block(&mut block);
+      self.key.decrypt_block(block);
 
       for j in 0..16 {
         if i == n - 1 {
@@ -106,30 +115,116 @@ impl Decrypter for CBC {
       }
     }
 
-    unpad(bb)
+    unpad(bb).map_err(|_| Error::Padding)
+  }
+}
+
+pub struct CTR {
+  key: aes::Aes128,
+  nonce: u64,
+}
+
+impl CTR {
+  pub fn new(k: &[u8], nonce: u64) -> Result<CTR, &'static str> {
+    match aes::Aes128::new_from_slice(k) {
+      Ok(k) => Ok(CTR { key: k, nonce }),
+      Err(_) => Err("bad key"),
+    }
+  }
+
+  // keystream_block encrypts the nonce and block counter, each as 64-bit little endian.
+  fn keystream_block(&self, counter: u64) -> Vec<u8> {
+    let mut bb = Vec::from(self.nonce.to_le_bytes());
+    bb.extend(counter.to_le_bytes());
+
+    let block = aes::Block::from_mut_slice(&mut bb);
+    self.key.encrypt_block(block);
+
+    bb
+  }
+
+  // apply_at xors bb with the keystream from byte offset on, generating only the blocks it covers.
+  fn apply_at(&self, offset: usize, bb: &mut [u8]) {
+    let mut i = 0;
+    while i < bb.len() {
+      let pos = offset + i;
+      let ks = self.keystream_block((pos / 16) as u64);
+
+      let start = pos % 16;
+      let n = (16 - start).min(bb.len() - i);
+      for j in 0..n {
+        bb[i + j] ^= ks[start + j];
+      }
+
+      i += n;
+    }
+  }
+
+  fn apply(&self, input: &[u8]) -> Vec<u8> {
+    let mut bb = Vec::from(input);
+    self.apply_at(0, &mut bb);
+
+    bb
+  }
+
+  // edit replaces the plaintext under ciphertext at offset with newtext, in place, without touching
+  // the rest of the buffer. The ciphertext grows if newtext runs past its end.
+  pub fn edit(
+    &self,
+    ciphertext: &mut Vec<u8>,
+    offset: usize,
+    newtext: &[u8],
+  ) -> Result<(), &'static str> {
+    if offset > ciphertext.len() {
+      return Err("offset past end of ciphertext");
+    }
+
+    let mut bb = Vec::from(newtext);
+    self.apply_at(offset, &mut bb);
+
+    let end = offset + bb.len();
+    if end > ciphertext.len() {
+      ciphertext.resize(end, 0);
+    }
+    ciphertext[offset..end].copy_from_slice(&bb);
+
+    Ok(())
+  }
+}
+
+impl Encrypter for CTR {
+  fn encrypt(&self, plain: &[u8]) -> Vec<u8> {
+    self.apply(plain)
   }
 }
 
+impl Decrypter for CTR {
+  fn decrypt(&self, enc: &[u8]) -> Result<Vec<u8>, Error> {
+    Ok(self.apply(enc))
+  }
+}
+
+// pad applies PKCS#7 padding, adding a whole block of padding when bb is already aligned so that
+// unpad never mistakes trailing plaintext for padding.
 pub fn pad(bb: &[u8], n: usize) -> Vec<u8> {
   let mut padded = Vec::from(bb);
 
-  if bb.len() % n > 0 {
-    let diff = n - bb.len() % n;
-    padded.extend([diff as u8].repeat(diff));
-  }
+  let diff = n - bb.len() % n;
+  padded.extend([diff as u8].repeat(diff));
 
   padded
 }
 
+// unpad strips the padding added by pad, which is always 1 to 16 bytes.
 pub fn unpad(mut bb: Vec<u8>) -> Result<Vec<u8>, &'static str> {
   let l = bb.len();
   if l == 0 {
-    return Ok(bb);
+    return Err("padding error");
   }
 
   let last = bb[l - 1];
-  if last > 15 {
-    return Ok(bb);
+  if last == 0 || last > 16 || last as usize > l {
+    return Err("padding error");
   }
 
   for i in 1..(last as usize) {
@@ -5,10 +5,8 @@ pub struct Injector<'a, T: aes::Encrypter> {
 }
 
 impl<'a, T: aes::Encrypter> Injector<'a, T> {
-  pub fn new(encrypter: &T) -> Injector<T> {
-    Injector {
-      encrypter: encrypter,
-    }
+  pub fn new(encrypter: &T) -> Injector<'_, T> {
+    Injector { encrypter }
   }
 
   // inject injects a ciphertext block in the nth block position that decodes to text, given the next plaintext block.
@@ -17,7 +15,6 @@ impl<'a, T: aes::Encrypter> Injector<'a, T> {
     assert_eq!(16, text.len());
     assert_eq!(16, next.len());
 
-
     let block = vec![0; 16];
     let mut enc = self.encrypter.encrypt(&block);
     let cur = &enc[n * 16..(n + 1) * 16];
@@ -34,6 +31,42 @@ impl<'a, T: aes::Encrypter> Injector<'a, T> {
   }
 }
 
+pub struct KeyRecoverer<'a, T: aes::Decrypter> {
+  decrypter: &'a T,
+}
+
+impl<'a, T: aes::Decrypter> KeyRecoverer<'a, T> {
+  pub fn new(decrypter: &T) -> KeyRecoverer<'_, T> {
+    KeyRecoverer { decrypter }
+  }
+
+  // recover finds the key of a decrypter that uses its key as the iv, given a ciphertext of at least
+  // three blocks, provided the decrypter hands back the plaintexts it rejects.
+  pub fn recover(&self, ciphertext: &[u8]) -> Result<Vec<u8>, &'static str> {
+    if ciphertext.len() < 48 {
+      return Err("ciphertext must be at least three blocks long");
+    }
+
+    // C1, 0, C1 decrypts to P1, garbage, P1 ^ iv. The rest of the original ciphertext follows, so
+    // the padding still checks out.
+    let mut forged = Vec::from(&ciphertext[..16]);
+    forged.extend([0; 16]);
+    forged.extend(&ciphertext[..16]);
+    forged.extend(&ciphertext[16..]);
+
+    match self.decrypter.decrypt(&forged) {
+      Err(aes::Error::Rejected(plain)) => {
+        let mut key = Vec::from(&plain[..16]);
+        xor(&mut key, &plain[32..48]);
+
+        Ok(key)
+      }
+      Err(aes::Error::Padding) => Err("forged ciphertext has bad padding"),
+      Ok(_) => Err("forged plaintext was not rejected"),
+    }
+  }
+}
+
 fn xor(x: &mut [u8], y: &[u8]) {
   for i in 0..16 {
     x[i] ^= y[i]
@@ -0,0 +1,105 @@
+use crate::aes;
+use crate::ScoredXOR;
+
+pub struct FixedNonceDecrypter {
+  ciphertexts: Vec<Vec<u8>>,
+  keystream: Vec<u8>,
+}
+
+impl FixedNonceDecrypter {
+  // new guesses the keystream shared by ciphertexts encrypted under the same key and nonce. Truncated
+  // to the shortest ciphertext, they form one repeating-key xor, broken a column at a time.
+  pub fn new(ciphertexts: &[Vec<u8>]) -> FixedNonceDecrypter {
+    let l = ciphertexts.iter().map(|c| c.len()).min().unwrap_or(0);
+
+    let mut keystream = Vec::<u8>::new();
+    for i in 0..l {
+      let column: Vec<u8> = ciphertexts.iter().map(|c| c[i]).collect();
+      keystream.push(ScoredXOR::best(&column).key);
+    }
+
+    FixedNonceDecrypter {
+      ciphertexts: ciphertexts.to_vec(),
+      keystream,
+    }
+  }
+
+  pub fn keystream(&self) -> &[u8] {
+    &self.keystream
+  }
+
+  // plaintexts decrypts every ciphertext as far as the keystream is known.
+  pub fn plaintexts(&self) -> Vec<Vec<u8>> {
+    self
+      .ciphertexts
+      .iter()
+      .map(|c| c.iter().zip(&self.keystream).map(|(a, b)| a ^ b).collect())
+      .collect()
+  }
+
+  // pin fixes the keystream so that row decrypts to known from offset onwards, which corrects the
+  // same columns of every other row. Pinning past the end of the keystream extends it.
+  pub fn pin(&mut self, row: usize, offset: usize, known: &[u8]) -> Result<(), &'static str> {
+    let ct = match self.ciphertexts.get(row) {
+      Some(ct) => ct,
+      None => return Err("no such row"),
+    };
+    if offset > self.keystream.len() {
+      return Err("offset leaves a gap in the keystream");
+    }
+    if offset + known.len() > ct.len() {
+      return Err("known text runs past the end of the row");
+    }
+
+    for (i, b) in known.iter().enumerate() {
+      let k = ct[offset + i] ^ b;
+      if offset + i < self.keystream.len() {
+        self.keystream[offset + i] = k;
+      } else {
+        self.keystream.push(k);
+      }
+    }
+
+    Ok(())
+  }
+}
+
+pub struct EditDecrypter<'a, F: Fn(&[u8], usize, &[u8]) -> Vec<u8>> {
+  edit: &'a F,
+}
+
+impl<'a, F: Fn(&[u8], usize, &[u8]) -> Vec<u8>> EditDecrypter<'a, F> {
+  // new wraps an edit function, which returns ciphertext with the plaintext at offset replaced.
+  pub fn new(edit: &F) -> EditDecrypter<'_, F> {
+    EditDecrypter { edit }
+  }
+
+  // decrypt recovers the plaintext of ciphertext by writing the ciphertext over itself: the
+  // keystream is xored in once more, which leaves the plaintext.
+  pub fn decrypt(&self, ciphertext: &[u8]) -> Vec<u8> {
+    (self.edit)(ciphertext, 0, ciphertext)
+  }
+}
+
+pub struct Injector<'a, T: aes::Encrypter> {
+  encrypter: &'a T,
+}
+
+impl<'a, T: aes::Encrypter> Injector<'a, T> {
+  pub fn new(encrypter: &T) -> Injector<'_, T> {
+    Injector { encrypter }
+  }
+
+  // inject returns a ciphertext that decrypts to text where the encrypter placed our input, found
+  // by detecting the length of whatever it puts in front.
+  pub fn inject(&self, text: &str) -> Vec<u8> {
+    let pl = crate::prefix_len(self.encrypter, 1);
+
+    let mut enc = self.encrypter.encrypt(&vec![0; text.len()]);
+    for (i, b) in text.bytes().enumerate() {
+      enc[pl + i] ^= b;
+    }
+
+    enc
+  }
+}
@@ -0,0 +1,56 @@
+use num_bigint::{BigUint, RandBigInt};
+use rand::rngs::OsRng;
+
+use crate::hash::sha1::Sha1;
+
+// NIST_P is the 1536-bit MODP prime from RFC 3526, used with generator 2.
+pub const NIST_P: &str = "ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b139b22514a08798e3404ddef9519b3cd3a431b302b0a6df25f14374fe1356d6d51c245e485b576625e7ec6f44c42e9a637ed6b0bff5cb6f406b7edee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3dc2007cb8a163bf0598da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb9ed529077096966d670c354e4abc9804f1746c08ca237327ffffffffffffffff";
+
+pub fn nist_p() -> BigUint {
+  BigUint::parse_bytes(NIST_P.as_bytes(), 16).unwrap()
+}
+
+pub fn nist_g() -> BigUint {
+  BigUint::from(2u32)
+}
+
+// DH is one side of a Diffie-Hellman exchange over the group given by p and g.
+pub struct DH {
+  pub p: BigUint,
+  pub g: BigUint,
+  private: BigUint,
+  pub public: BigUint,
+}
+
+impl DH {
+  // new picks a random private key in [1, p) and derives the public key from it.
+  pub fn new(p: &BigUint, g: &BigUint) -> DH {
+    let private = OsRng.gen_biguint_range(&BigUint::from(1u32), p);
+    let public = g.modpow(&private, p);
+
+    DH {
+      p: p.clone(),
+      g: g.clone(),
+      private,
+      public,
+    }
+  }
+
+  pub fn nist() -> DH {
+    DH::new(&nist_p(), &nist_g())
+  }
+
+  pub fn shared_secret(&self, other: &BigUint) -> BigUint {
+    other.modpow(&self.private, &self.p)
+  }
+
+  // key derives an AES-128 key from the secret shared with other.
+  pub fn key(&self, other: &BigUint) -> Vec<u8> {
+    aes_key(&self.shared_secret(other))
+  }
+}
+
+// aes_key hashes a shared secret down to an AES-128 key, as the first 16 bytes of its SHA-1.
+pub fn aes_key(secret: &BigUint) -> Vec<u8> {
+  Vec::from(&Sha1::digest(&secret.to_bytes_be())[..16])
+}
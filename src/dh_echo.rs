@@ -0,0 +1,366 @@
+use num_bigint::BigUint;
+use num_traits::One;
+use rand::{rngs::OsRng, RngCore};
+use std::thread;
+
+use crate::actor::{self, Endpoint, Mitm, Side};
+use crate::aes::{self, Decrypter, Encrypter};
+use crate::dh::{self, DH};
+
+pub enum Message {
+  // Hello opens the exchange with the group and the sender's public key.
+  Hello {
+    p: BigUint,
+    g: BigUint,
+    public: BigUint,
+  },
+  // Group proposes the group for a negotiated exchange.
+  Group {
+    p: BigUint,
+    g: BigUint,
+  },
+  // Ack accepts a proposed group, repeating it.
+  Ack {
+    p: BigUint,
+    g: BigUint,
+  },
+  // Key answers Hello with the responder's public key, and carries either side's public key once a
+  // group is negotiated.
+  Key(BigUint),
+  // Data is an AES-CBC ciphertext with its iv appended.
+  Data(Vec<u8>),
+}
+
+// seal encrypts plain under key with a random iv, which goes after the ciphertext.
+pub fn seal(key: &[u8], plain: &[u8]) -> Result<Vec<u8>, &'static str> {
+  let mut iv = [0u8; 16];
+  OsRng.fill_bytes(&mut iv);
+
+  let mut sealed = aes::CBC::new(key, &iv)?.encrypt(plain);
+  sealed.extend_from_slice(&iv);
+
+  Ok(sealed)
+}
+
+// open decrypts a message made by seal.
+pub fn open(key: &[u8], sealed: &[u8]) -> Result<Vec<u8>, &'static str> {
+  if sealed.len() < 16 {
+    return Err("message too short to carry an iv");
+  }
+
+  let (ciphertext, iv) = sealed.split_at(sealed.len() - 16);
+  aes::CBC::new(key, iv)?
+    .decrypt(ciphertext)
+    .map_err(|_| "bad padding")
+}
+
+// alice opens an exchange over the NIST group, sends text encrypted under the shared key and
+// returns what comes back.
+pub fn alice(link: Endpoint<Message>, text: &[u8]) -> Result<Vec<u8>, &'static str> {
+  let a = DH::nist();
+  link.send(Message::Hello {
+    p: a.p.clone(),
+    g: a.g.clone(),
+    public: a.public.clone(),
+  })?;
+
+  let key = match link.recv()? {
+    Message::Key(public) => a.key(&public),
+    _ => return Err("expected a public key"),
+  };
+
+  link.send(Message::Data(seal(&key, text)?))?;
+  match link.recv()? {
+    Message::Data(sealed) => open(&key, &sealed),
+    _ => Err("expected data"),
+  }
+}
+
+// bob answers an exchange, then echoes the message he is sent back re-encrypted, and returns it.
+pub fn bob(link: Endpoint<Message>) -> Result<Vec<u8>, &'static str> {
+  let (b, other) = match link.recv()? {
+    Message::Hello { p, g, public } => (DH::new(&p, &g), public),
+    _ => return Err("expected hello"),
+  };
+  link.send(Message::Key(b.public.clone()))?;
+  let key = b.key(&other);
+
+  let text = match link.recv()? {
+    Message::Data(sealed) => open(&key, &sealed)?,
+    _ => return Err("expected data"),
+  };
+  link.send(Message::Data(seal(&key, &text)?))?;
+
+  Ok(text)
+}
+
+// echo runs alice against bob, with mitm on the wire if there is one, and returns what alice got
+// back.
+pub fn echo(text: &[u8], mitm: Option<&mut dyn Mitm<Message>>) -> Result<Vec<u8>, &'static str> {
+  run(move |a| alice(a, text), bob, mitm)
+}
+
+// run plays alice and bob against each other in their own threads, relaying through mitm if there
+// is one, and returns alice's result once both are done.
+fn run<A, B>(
+  alice: A,
+  bob: B,
+  mitm: Option<&mut dyn Mitm<Message>>,
+) -> Result<Vec<u8>, &'static str>
+where
+  A: FnOnce(Endpoint<Message>) -> Result<Vec<u8>, &'static str> + Send,
+  B: FnOnce(Endpoint<Message>) -> Result<Vec<u8>, &'static str> + Send,
+{
+  let (a, facing_a) = actor::pair();
+  let (wire, b) = match mitm {
+    Some(mitm) => {
+      let (facing_b, b) = actor::pair();
+      (Some((facing_a, facing_b, mitm)), b)
+    }
+    None => (None, facing_a),
+  };
+
+  thread::scope(|s| {
+    let alice = s.spawn(move || alice(a));
+    let bob = s.spawn(move || bob(b));
+    if let Some((facing_a, facing_b, mitm)) = wire {
+      actor::relay(facing_a, facing_b, mitm);
+    }
+
+    let echoed = alice.join().map_err(|_| "alice panicked")?;
+    let echoing = bob.join().map_err(|_| "bob panicked")?;
+
+    // Whoever gave up first has the reason why, the other only saw a hang up.
+    match (echoed, echoing) {
+      (Err(actor::HUNG_UP), Err(e)) => Err(e),
+      (echoed, Ok(_)) | (echoed @ Err(_), _) => echoed,
+      (Ok(_), Err(e)) => Err(e),
+    }
+  })
+}
+
+// KeyReplacer swaps both public keys for p, which makes the shared secret 0 on either side, and
+// reads every message that follows.
+#[derive(Default)]
+pub struct KeyReplacer {
+  p: BigUint,
+  messages: Vec<Vec<u8>>,
+}
+
+impl KeyReplacer {
+  pub fn new() -> KeyReplacer {
+    KeyReplacer::default()
+  }
+
+  // messages is the plaintext of everything sent after the exchange, in order.
+  pub fn messages(&self) -> &[Vec<u8>] {
+    &self.messages
+  }
+}
+
+impl Mitm<Message> for KeyReplacer {
+  fn intercept(&mut self, _: Side, message: Message) -> Message {
+    match message {
+      Message::Hello { p, g, .. } => {
+        self.p = p.clone();
+        Message::Hello {
+          public: p.clone(),
+          p,
+          g,
+        }
+      }
+      Message::Key(_) => Message::Key(self.p.clone()),
+      Message::Data(sealed) => {
+        if let Ok(plain) = open(&dh::aes_key(&BigUint::default()), &sealed) {
+          self.messages.push(plain);
+        }
+        Message::Data(sealed)
+      }
+      m => m,
+    }
+  }
+}
+
+// check_group rejects generators that confine the shared secret to a handful of values: anything
+// outside 2..=p-2.
+fn check_group(p: &BigUint, g: &BigUint) -> Result<(), &'static str> {
+  let one = BigUint::one();
+  if *g <= one || *g >= p - &one {
+    return Err("unsafe generator");
+  }
+
+  Ok(())
+}
+
+// negotiating_alice proposes the NIST group, runs the exchange over whatever group is acknowledged,
+// then sends text and returns what comes back. A strict alice insists on the group she proposed.
+pub fn negotiating_alice(
+  link: Endpoint<Message>,
+  text: &[u8],
+  strict: bool,
+) -> Result<Vec<u8>, &'static str> {
+  let (p, g) = (dh::nist_p(), dh::nist_g());
+  link.send(Message::Group {
+    p: p.clone(),
+    g: g.clone(),
+  })?;
+
+  let a = match link.recv()? {
+    Message::Ack { p: ap, g: ag } => {
+      if strict && (ap != p || ag != g) {
+        return Err("group changed in negotiation");
+      }
+      DH::new(&ap, &ag)
+    }
+    _ => return Err("expected ack"),
+  };
+
+  link.send(Message::Key(a.public.clone()))?;
+  let key = match link.recv()? {
+    Message::Key(public) => a.key(&public),
+    _ => return Err("expected a public key"),
+  };
+
+  link.send(Message::Data(seal(&key, text)?))?;
+  match link.recv()? {
+    Message::Data(sealed) => open(&key, &sealed),
+    _ => Err("expected data"),
+  }
+}
+
+// negotiating_bob accepts the group he is offered, then echoes a message as bob does. A strict bob
+// refuses unsafe generators.
+pub fn negotiating_bob(link: Endpoint<Message>, strict: bool) -> Result<Vec<u8>, &'static str> {
+  let b = match link.recv()? {
+    Message::Group { p, g } => {
+      if strict {
+        check_group(&p, &g)?;
+      }
+      link.send(Message::Ack {
+        p: p.clone(),
+        g: g.clone(),
+      })?;
+      DH::new(&p, &g)
+    }
+    _ => return Err("expected a group"),
+  };
+
+  let key = match link.recv()? {
+    Message::Key(public) => {
+      link.send(Message::Key(b.public.clone()))?;
+      b.key(&public)
+    }
+    _ => return Err("expected a public key"),
+  };
+
+  let text = match link.recv()? {
+    Message::Data(sealed) => open(&key, &sealed)?,
+    _ => return Err("expected data"),
+  };
+  link.send(Message::Data(seal(&key, &text)?))?;
+
+  Ok(text)
+}
+
+// negotiated_echo runs negotiating_alice against negotiating_bob, both strict or both not, with
+// mitm on the wire if there is one, and returns what alice got back.
+pub fn negotiated_echo(
+  text: &[u8],
+  strict: bool,
+  mitm: Option<&mut dyn Mitm<Message>>,
+) -> Result<Vec<u8>, &'static str> {
+  run(
+    move |a| negotiating_alice(a, text, strict),
+    move |b| negotiating_bob(b, strict),
+    mitm,
+  )
+}
+
+// MaliciousG is a generator to slip into a negotiation in place of the real one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MaliciousG {
+  One,
+  P,
+  PMinusOne,
+}
+
+// GInjector rewrites the negotiated generator on its way to both sides, so that each computes its
+// keys over a group where the shared secret can be predicted, and reads every message that
+// follows.
+pub struct GInjector {
+  g: MaliciousG,
+  p: BigUint,
+  keys: Vec<BigUint>,
+  messages: Vec<Vec<u8>>,
+}
+
+impl GInjector {
+  pub fn new(g: MaliciousG) -> GInjector {
+    GInjector {
+      g,
+      p: BigUint::default(),
+      keys: Vec::new(),
+      messages: Vec::new(),
+    }
+  }
+
+  // messages is the plaintext of everything sent after the exchange, in order.
+  pub fn messages(&self) -> &[Vec<u8>] {
+    &self.messages
+  }
+
+  fn generator(&self, p: &BigUint) -> BigUint {
+    match self.g {
+      MaliciousG::One => BigUint::one(),
+      MaliciousG::P => p.clone(),
+      MaliciousG::PMinusOne => p - BigUint::one(),
+    }
+  }
+
+  // secret predicts the shared secret from the public keys seen. With g = 1 it is always 1, and with
+  // g = p always 0. With g = p - 1 every power is 1 or p - 1, and the secret is p - 1 only when
+  // both private keys are odd, which shows as both public keys being p - 1.
+  pub fn secret(&self) -> BigUint {
+    match self.g {
+      MaliciousG::One => BigUint::one(),
+      MaliciousG::P => BigUint::default(),
+      MaliciousG::PMinusOne => {
+        let minus_one = &self.p - BigUint::one();
+        if self.keys.len() == 2 && self.keys.iter().all(|k| *k == minus_one) {
+          minus_one
+        } else {
+          BigUint::one()
+        }
+      }
+    }
+  }
+}
+
+impl Mitm<Message> for GInjector {
+  fn intercept(&mut self, _: Side, message: Message) -> Message {
+    match message {
+      Message::Group { p, .. } => {
+        self.p = p.clone();
+        Message::Group {
+          g: self.generator(&p),
+          p,
+        }
+      }
+      Message::Ack { p, .. } => Message::Ack {
+        g: self.generator(&p),
+        p,
+      },
+      Message::Key(public) => {
+        self.keys.push(public.clone());
+        Message::Key(public)
+      }
+      Message::Data(sealed) => {
+        if let Ok(plain) = open(&dh::aes_key(&self.secret()), &sealed) {
+          self.messages.push(plain);
+        }
+        Message::Data(sealed)
+      }
+      m => m,
+    }
+  }
+}
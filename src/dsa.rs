@@ -0,0 +1,263 @@
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::{One, Zero};
+use rand::rngs::OsRng;
+use std::collections::HashMap;
+
+use crate::hash::sha1::Sha1;
+use crate::rsa::invmod;
+
+const P: &str = "800000000000000089e1855218a0e7dac38136ffafa72eda7859f2171e25e65eac698c1702578b07dc2a1076da241c76c62d374d8389ea5aeffd3226a0530cc565f3bf6b50929139ebeac04f48c3c84afb796d61e5a4f9a8fda812ab59494232c7d2b4deb50aa18ee9e132bfa85ac4374d7f9091abc3d015efc871a584471bb1";
+const Q: &str = "f4f47f05794b256174bba6e9b396a7707e563c5b";
+const G: &str = "5958c9d3898b224b12672c0b98e06c60df923cb8bc999d119458fef538b8fa4046c8db53039db620c094c9fa077ef389b5322a559946a71903f990f1f7e0e025e2d7f7cf494aff1a0470f5b64c36b625a097f1651fe775323556fe00b3608c887892878480e99041be601a62166ca6894bdd41a7054ec89f756ba9fc95302291";
+
+// Params are the domain parameters: a prime p, a prime q dividing p - 1, and g generating the
+// subgroup of order q.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Params {
+  pub p: BigUint,
+  pub q: BigUint,
+  pub g: BigUint,
+}
+
+impl Default for Params {
+  // default is the 1024 bit group the challenges use.
+  fn default() -> Params {
+    let int = |s: &str| BigUint::parse_bytes(s.as_bytes(), 16).unwrap();
+
+    Params {
+      p: int(P),
+      q: int(Q),
+      g: int(G),
+    }
+  }
+}
+
+impl Params {
+  // check rejects parameters that don't form a subgroup of order q: g must lie strictly between 1
+  // and p, q must divide p - 1, and g^q must be 1.
+  pub fn check(&self) -> Result<(), &'static str> {
+    let Params { p, q, g } = self;
+    if q.is_zero() || !((p - 1u32) % q).is_zero() {
+      return Err("q does not divide p - 1");
+    }
+    if *g <= BigUint::one() || g >= p {
+      return Err("g out of range");
+    }
+    if !g.modpow(q, p).is_one() {
+      return Err("g does not generate a subgroup of order q");
+    }
+
+    Ok(())
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Signature {
+  pub r: BigUint,
+  pub s: BigUint,
+}
+
+// hash is the SHA-1 of message as an integer, which is what gets signed.
+pub fn hash(message: &[u8]) -> BigUint {
+  BigUint::from_bytes_be(&Sha1::digest(message))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PublicKey {
+  params: Params,
+  pub y: BigUint,
+  validate: bool,
+  // checked caches params.check(), so verifying doesn't repeat its exponentiation every time. The
+  // params are private so that the cache can't go stale.
+  checked: Result<(), &'static str>,
+}
+
+impl PublicKey {
+  pub fn new(params: &Params, y: &BigUint) -> PublicKey {
+    PublicKey {
+      params: params.clone(),
+      y: y.clone(),
+      validate: true,
+      checked: params.check(),
+    }
+  }
+
+  // with_validation sets whether verify checks the domain parameters and that r and s lie in
+  // 1..q. Without it, tampered parameters let through signatures that prove nothing.
+  pub fn with_validation(self, validate: bool) -> PublicKey {
+    PublicKey { validate, ..self }
+  }
+
+  pub fn params(&self) -> &Params {
+    &self.params
+  }
+
+  pub fn verify(&self, message: &[u8], signature: &Signature) -> bool {
+    self.verify_hash(&hash(message), signature)
+  }
+
+  // verify_hash checks a signature on a message given only its hash.
+  pub fn verify_hash(&self, h: &BigUint, signature: &Signature) -> bool {
+    let Params { p, q, g } = &self.params;
+    let Signature { r, s } = signature;
+    if self.validate
+      && (self.checked.is_err() || r.is_zero() || r >= q || s.is_zero() || s >= q)
+    {
+      return false;
+    }
+
+    let w = match invmod(s, q) {
+      Some(w) => w,
+      None => return false,
+    };
+    let u1 = (h * &w) % q;
+    let u2 = (r * &w) % q;
+    let v = ((g.modpow(&u1, p) * self.y.modpow(&u2, p)) % p) % q;
+
+    v == *r
+  }
+}
+
+// DSA is a DSA key pair.
+pub struct DSA {
+  public: PublicKey,
+  x: BigUint,
+}
+
+impl DSA {
+  pub fn new(params: &Params) -> DSA {
+    let x = OsRng.gen_biguint_range(&BigUint::one(), &params.q);
+
+    DSA {
+      public: PublicKey::new(params, &params.g.modpow(&x, &params.p)),
+      x,
+    }
+  }
+
+  // with_validation sets whether signing checks the domain parameters first and refuses to produce
+  // r = 0, and whether the public key validates, as for PublicKey::with_validation.
+  pub fn with_validation(self, validate: bool) -> DSA {
+    DSA {
+      public: self.public.with_validation(validate),
+      ..self
+    }
+  }
+
+  pub fn public(&self) -> &PublicKey {
+    &self.public
+  }
+
+  pub fn sign(&self, message: &[u8]) -> Result<Signature, &'static str> {
+    if self.public.validate {
+      self.public.checked?;
+    }
+
+    let q = &self.public.params.q;
+    loop {
+      let k = OsRng.gen_biguint_range(&BigUint::one(), q);
+      if let Some(signature) = self.sign_with_nonce(message, &k) {
+        return Ok(signature);
+      }
+    }
+  }
+
+  // sign_with_nonce signs with the given k, which had better be secret, random and used once. It
+  // fails for the rare k that gives r = 0 or s = 0.
+  pub fn sign_with_nonce(&self, message: &[u8], k: &BigUint) -> Option<Signature> {
+    let Params { p, q, g } = &self.public.params;
+
+    let r = g.modpow(k, p) % q;
+    let s = (invmod(k, q)? * (hash(message) + &self.x * &r)) % q;
+    if (self.public.validate && r.is_zero()) || s.is_zero() {
+      return None;
+    }
+
+    Some(Signature { r, s })
+  }
+}
+
+// sub_mod is a - b mod m.
+fn sub_mod(a: &BigUint, b: &BigUint, m: &BigUint) -> BigUint {
+  (a % m + m - b % m) % m
+}
+
+// key_from_nonce recovers the private key from a signature on the hash h made with the nonce k,
+// as x = (s * k - h) / r mod q.
+pub fn key_from_nonce(
+  params: &Params,
+  h: &BigUint,
+  signature: &Signature,
+  k: &BigUint,
+) -> Option<BigUint> {
+  let q = &params.q;
+  let sk = (&signature.s * k) % q;
+
+  Some((sub_mod(&sk, h, q) * invmod(&signature.r, q)?) % q)
+}
+
+// brute_force_key finds the private key behind a signature on the hash h whose nonce was at most
+// max_k, trying each k against r before solving for the key.
+pub fn brute_force_key(
+  key: &PublicKey,
+  h: &BigUint,
+  signature: &Signature,
+  max_k: u32,
+) -> Option<BigUint> {
+  let Params { p, q, g } = &key.params;
+
+  let mut gk = BigUint::one();
+  for k in 0..=max_k {
+    if gk.clone() % q == signature.r {
+      if let Some(x) = key_from_nonce(&key.params, h, signature, &BigUint::from(k)) {
+        if g.modpow(&x, p) == key.y {
+          return Some(x);
+        }
+      }
+    }
+    gk = (gk * g) % p;
+  }
+
+  None
+}
+
+// find_repeated_nonce looks through signatures made with the same key, given with the hashes they
+// sign, for two that share a nonce, which shows as a shared r. The nonce is then
+// (h1 - h2) / (s1 - s2) mod q, and gives away the key.
+pub fn find_repeated_nonce(key: &PublicKey, signed: &[(BigUint, Signature)]) -> Option<BigUint> {
+  let Params { p, q, g } = &key.params;
+
+  let mut seen = HashMap::<&BigUint, &(BigUint, Signature)>::new();
+  for pair in signed {
+    let (h2, sig2) = pair;
+    let (h1, sig1) = match seen.insert(&sig2.r, pair) {
+      Some(earlier) => earlier,
+      None => continue,
+    };
+
+    let ds = match invmod(&sub_mod(&sig1.s, &sig2.s, q), q) {
+      Some(ds) => ds,
+      None => continue,
+    };
+    let k = (sub_mod(h1, h2, q) * ds) % q;
+    if let Some(x) = key_from_nonce(&key.params, h1, sig1, &k) {
+      if g.modpow(&x, p) == key.y {
+        return Some(x);
+      }
+    }
+  }
+
+  None
+}
+
+// magic_signature makes a signature that verifies for any message under key, if its generator is
+// p + 1, or anything else that is 1 mod p, and nobody checks. Then g^u1 is always 1, and
+// v = y^u2 mod p mod q, where u2 = r / s = z for the r and s picked here.
+pub fn magic_signature(key: &PublicKey) -> Option<Signature> {
+  let Params { p, q, .. } = &key.params;
+  let z = OsRng.gen_biguint_range(&BigUint::one(), q);
+
+  let r = key.y.modpow(&z, p) % q;
+  let s = (&r * invmod(&z, q)?) % q;
+
+  Some(Signature { r, s })
+}
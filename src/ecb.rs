@@ -5,10 +5,8 @@ pub struct Decrypter<'a, T: aes::Encrypter> {
 }
 
 impl<'a, T: aes::Encrypter> Decrypter<'a, T> {
-  pub fn new(encrypter: &T) -> Decrypter<T> {
-    Decrypter {
-      encrypter: encrypter,
-    }
+  pub fn new(encrypter: &T) -> Decrypter<'_, T> {
+    Decrypter { encrypter }
   }
 
   pub fn decrypt(&self) -> Result<Vec<u8>, &str> {
@@ -30,26 +28,7 @@ impl<'a, T: aes::Encrypter> Decrypter<'a, T> {
   }
 
   fn prefix_len(&self) -> usize {
-    let raw = self.encrypter.encrypt(&[]);
-    let shifted = self.encrypter.encrypt(&vec![0; 32]);
-    let mut j: usize = 0;
-    for i in 0..(raw.len() / 16) {
-      if shifted[i * 16..(1 + i) * 16] != raw[i * 16..(i + 1) * 16] {
-        j = i + 1;
-        break;
-      }
-    }
-    let zero_block = &shifted[j * 16..(j + 1) * 16];
-
-    for i in 0..16 {
-      let block = vec![0; 16 + i];
-      let e = self.encrypter.encrypt(&block);
-      if &e[j * 16..(j + 1) * 16] == zero_block {
-        return j * 16 - i;
-      }
-    }
-
-    (j - 1) * 16
+    crate::prefix_len(self.encrypter, 16)
   }
 
   fn suffix_len(&self) -> usize {
@@ -0,0 +1,44 @@
+pub mod md4;
+pub mod sha1;
+pub mod sha256;
+
+pub enum Endian {
+  Big,
+  Little,
+}
+
+// md_padding returns the Merkle–Damgård padding appended to a message of len bytes: a one bit, zeros
+// up to 56 bytes into a 64 byte block, then the message length in bits.
+pub fn md_padding(len: u64, endian: Endian) -> Vec<u8> {
+  let mut pad = vec![0x80];
+  while (len as usize + pad.len()) % 64 != 56 {
+    pad.push(0);
+  }
+
+  let bits = len.wrapping_mul(8);
+  match endian {
+    Endian::Big => pad.extend(bits.to_be_bytes()),
+    Endian::Little => pad.extend(bits.to_le_bytes()),
+  }
+
+  pad
+}
+
+// hmac computes HMAC for a hash with 64 byte blocks, such as SHA-1 or SHA-256, given its digest
+// function.
+pub fn hmac(digest: &dyn Fn(&[u8]) -> Vec<u8>, key: &[u8], message: &[u8]) -> Vec<u8> {
+  let mut k = if key.len() > 64 {
+    digest(key)
+  } else {
+    Vec::from(key)
+  };
+  k.resize(64, 0);
+
+  let mut inner: Vec<u8> = k.iter().map(|b| b ^ 0x36).collect();
+  inner.extend(message);
+
+  let mut outer: Vec<u8> = k.iter().map(|b| b ^ 0x5c).collect();
+  outer.extend(digest(&inner));
+
+  digest(&outer)
+}
@@ -0,0 +1,147 @@
+use crate::hash::{md_padding, Endian};
+
+const INIT: [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+
+// Md4 is a streaming MD4 whose chaining state can be read back and resumed from.
+pub struct Md4 {
+  h: [u32; 4],
+  len: u64,
+  buf: Vec<u8>,
+}
+
+impl Default for Md4 {
+  fn default() -> Md4 {
+    Md4::new()
+  }
+}
+
+impl Md4 {
+  pub fn new() -> Md4 {
+    Md4::from_state(INIT, 0)
+  }
+
+  // from_state resumes hashing as if len bytes, a multiple of 64, had already been processed into
+  // the chaining values h.
+  pub fn from_state(h: [u32; 4], len: u64) -> Md4 {
+    debug_assert!(len.is_multiple_of(64), "resumed length must be a whole number of blocks");
+
+    Md4 {
+      h,
+      len,
+      buf: Vec::new(),
+    }
+  }
+
+  // state returns the chaining values and the number of bytes compressed into them, leaving out
+  // any bytes still buffered short of a full block.
+  pub fn state(&self) -> ([u32; 4], u64) {
+    (self.h, self.len - self.buf.len() as u64)
+  }
+
+  // digest hashes bb in one go.
+  pub fn digest(bb: &[u8]) -> [u8; 16] {
+    let mut s = Md4::new();
+    s.update(bb);
+    s.finalize()
+  }
+
+  pub fn update(&mut self, bb: &[u8]) {
+    self.len += bb.len() as u64;
+    self.buf.extend(bb);
+
+    let n = self.buf.len() / 64;
+    for i in 0..n {
+      let mut block = [0u8; 64];
+      block.copy_from_slice(&self.buf[i * 64..(i + 1) * 64]);
+      self.compress(&block);
+    }
+    self.buf.drain(..n * 64);
+  }
+
+  pub fn finalize(mut self) -> [u8; 16] {
+    let pad = md_padding(self.len, Endian::Little);
+    let len = self.len;
+    self.update(&pad);
+    self.len = len;
+
+    let mut digest = [0u8; 16];
+    for (i, h) in self.h.iter().enumerate() {
+      digest[i * 4..(i + 1) * 4].copy_from_slice(&h.to_le_bytes());
+    }
+
+    digest
+  }
+
+  fn compress(&mut self, block: &[u8; 64]) {
+    let mut x = [0u32; 16];
+    for (i, v) in x.iter_mut().enumerate() {
+      *v = u32::from_le_bytes([
+        block[i * 4],
+        block[i * 4 + 1],
+        block[i * 4 + 2],
+        block[i * 4 + 3],
+      ]);
+    }
+
+    let rounds = [
+      (0, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15], [3, 7, 11, 19]),
+      (0x5a827999, [0, 4, 8, 12, 1, 5, 9, 13, 2, 6, 10, 14, 3, 7, 11, 15], [3, 5, 9, 13]),
+      (0x6ed9eba1, [0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15], [3, 9, 11, 15]),
+    ];
+
+    // Each step updates one register from the other three, cycling a, d, c, b.
+    let mut r = self.h;
+    for (round, (k, order, shifts)) in rounds.iter().enumerate() {
+      for (i, j) in order.iter().enumerate() {
+        let a = (4 - i % 4) % 4;
+        let (b, c, d) = (r[(a + 1) % 4], r[(a + 2) % 4], r[(a + 3) % 4]);
+        let f = match round {
+          0 => (b & c) | (!b & d),
+          1 => (b & c) | (b & d) | (c & d),
+          _ => b ^ c ^ d,
+        };
+
+        r[a] = r[a]
+          .wrapping_add(f)
+          .wrapping_add(x[*j])
+          .wrapping_add(*k)
+          .rotate_left(shifts[i % 4]);
+      }
+    }
+
+    for (h, v) in self.h.iter_mut().zip(r) {
+      *h = h.wrapping_add(v);
+    }
+  }
+}
+
+// mac authenticates message by hashing it behind a secret key.
+pub fn mac(key: &[u8], message: &[u8]) -> [u8; 16] {
+  let mut s = Md4::new();
+  s.update(key);
+  s.update(message);
+  s.finalize()
+}
+
+// length_extend forges a mac for message, its glue padding and then extension, under a key of
+// key_len bytes, from the mac of message alone. It returns the forged message and its mac.
+pub fn length_extend(
+  mac: &[u8; 16],
+  message: &[u8],
+  key_len: usize,
+  extension: &[u8],
+) -> (Vec<u8>, [u8; 16]) {
+  let mut forged = Vec::from(message);
+  forged.extend(md_padding((key_len + message.len()) as u64, Endian::Little));
+
+  let mut h = [0u32; 4];
+  for (i, v) in h.iter_mut().enumerate() {
+    *v = u32::from_le_bytes([mac[i * 4], mac[i * 4 + 1], mac[i * 4 + 2], mac[i * 4 + 3]]);
+  }
+
+  let mut s = Md4::from_state(h, (key_len + forged.len()) as u64);
+  s.update(extension);
+  forged.extend(extension);
+
+  (forged, s.finalize())
+}
@@ -0,0 +1,154 @@
+use crate::hash::{self, md_padding, Endian};
+
+const INIT: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+// Sha1 is a streaming SHA-1 whose chaining state can be read back and resumed from.
+pub struct Sha1 {
+  h: [u32; 5],
+  len: u64,
+  buf: Vec<u8>,
+}
+
+impl Default for Sha1 {
+  fn default() -> Sha1 {
+    Sha1::new()
+  }
+}
+
+impl Sha1 {
+  pub fn new() -> Sha1 {
+    Sha1::from_state(INIT, 0)
+  }
+
+  // from_state resumes hashing as if len bytes, a multiple of 64, had already been processed into
+  // the chaining values h.
+  pub fn from_state(h: [u32; 5], len: u64) -> Sha1 {
+    debug_assert!(len.is_multiple_of(64), "resumed length must be a whole number of blocks");
+
+    Sha1 {
+      h,
+      len,
+      buf: Vec::new(),
+    }
+  }
+
+  // state returns the chaining values and the number of bytes compressed into them, leaving out
+  // any bytes still buffered short of a full block.
+  pub fn state(&self) -> ([u32; 5], u64) {
+    (self.h, self.len - self.buf.len() as u64)
+  }
+
+  // digest hashes bb in one go.
+  pub fn digest(bb: &[u8]) -> [u8; 20] {
+    let mut s = Sha1::new();
+    s.update(bb);
+    s.finalize()
+  }
+
+  pub fn update(&mut self, bb: &[u8]) {
+    self.len += bb.len() as u64;
+    self.buf.extend(bb);
+
+    let n = self.buf.len() / 64;
+    for i in 0..n {
+      let mut block = [0u8; 64];
+      block.copy_from_slice(&self.buf[i * 64..(i + 1) * 64]);
+      self.compress(&block);
+    }
+    self.buf.drain(..n * 64);
+  }
+
+  pub fn finalize(mut self) -> [u8; 20] {
+    let pad = md_padding(self.len, Endian::Big);
+    let len = self.len;
+    self.update(&pad);
+    self.len = len;
+
+    let mut digest = [0u8; 20];
+    for (i, h) in self.h.iter().enumerate() {
+      digest[i * 4..(i + 1) * 4].copy_from_slice(&h.to_be_bytes());
+    }
+
+    digest
+  }
+
+  fn compress(&mut self, block: &[u8; 64]) {
+    let mut w = [0u32; 80];
+    for i in 0..16 {
+      w[i] = u32::from_be_bytes([
+        block[i * 4],
+        block[i * 4 + 1],
+        block[i * 4 + 2],
+        block[i * 4 + 3],
+      ]);
+    }
+    for i in 16..80 {
+      w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e] = self.h;
+    for (i, wi) in w.iter().enumerate() {
+      let (f, k) = match i {
+        0..=19 => ((b & c) | (!b & d), 0x5a827999),
+        20..=39 => (b ^ c ^ d, 0x6ed9eba1),
+        40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+        _ => (b ^ c ^ d, 0xca62c1d6),
+      };
+
+      let t = a
+        .rotate_left(5)
+        .wrapping_add(f)
+        .wrapping_add(e)
+        .wrapping_add(k)
+        .wrapping_add(*wi);
+      e = d;
+      d = c;
+      c = b.rotate_left(30);
+      b = a;
+      a = t;
+    }
+
+    for (h, v) in self.h.iter_mut().zip([a, b, c, d, e]) {
+      *h = h.wrapping_add(v);
+    }
+  }
+}
+
+// mac authenticates message by hashing it behind a secret key.
+pub fn mac(key: &[u8], message: &[u8]) -> [u8; 20] {
+  let mut s = Sha1::new();
+  s.update(key);
+  s.update(message);
+  s.finalize()
+}
+
+// hmac authenticates message with HMAC-SHA1.
+pub fn hmac(key: &[u8], message: &[u8]) -> [u8; 20] {
+  let mut mac = [0u8; 20];
+  mac.copy_from_slice(&hash::hmac(&|bb| Vec::from(Sha1::digest(bb)), key, message));
+
+  mac
+}
+
+// length_extend forges a mac for message, its glue padding and then extension, under a key of
+// key_len bytes, from the mac of message alone. It returns the forged message and its mac.
+pub fn length_extend(
+  mac: &[u8; 20],
+  message: &[u8],
+  key_len: usize,
+  extension: &[u8],
+) -> (Vec<u8>, [u8; 20]) {
+  let mut forged = Vec::from(message);
+  forged.extend(md_padding((key_len + message.len()) as u64, Endian::Big));
+
+  let mut h = [0u32; 5];
+  for (i, v) in h.iter_mut().enumerate() {
+    *v = u32::from_be_bytes([mac[i * 4], mac[i * 4 + 1], mac[i * 4 + 2], mac[i * 4 + 3]]);
+  }
+
+  let mut s = Sha1::from_state(h, (key_len + forged.len()) as u64);
+  s.update(extension);
+  forged.extend(extension);
+
+  (forged, s.finalize())
+}
@@ -0,0 +1,130 @@
+use crate::hash::{self, md_padding, Endian};
+
+const INIT: [u32; 8] = [
+  0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const K: [u32; 64] = [
+  0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+  0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+  0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+  0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+  0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+  0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+  0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+  0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+// Sha256 is a streaming SHA-256.
+pub struct Sha256 {
+  h: [u32; 8],
+  len: u64,
+  buf: Vec<u8>,
+}
+
+impl Default for Sha256 {
+  fn default() -> Sha256 {
+    Sha256::new()
+  }
+}
+
+impl Sha256 {
+  pub fn new() -> Sha256 {
+    Sha256 {
+      h: INIT,
+      len: 0,
+      buf: Vec::new(),
+    }
+  }
+
+  // digest hashes bb in one go.
+  pub fn digest(bb: &[u8]) -> [u8; 32] {
+    let mut s = Sha256::new();
+    s.update(bb);
+    s.finalize()
+  }
+
+  pub fn update(&mut self, bb: &[u8]) {
+    self.len += bb.len() as u64;
+    self.buf.extend(bb);
+
+    let n = self.buf.len() / 64;
+    for i in 0..n {
+      let mut block = [0u8; 64];
+      block.copy_from_slice(&self.buf[i * 64..(i + 1) * 64]);
+      self.compress(&block);
+    }
+    self.buf.drain(..n * 64);
+  }
+
+  pub fn finalize(mut self) -> [u8; 32] {
+    let pad = md_padding(self.len, Endian::Big);
+    self.update(&pad);
+
+    let mut digest = [0u8; 32];
+    for (i, h) in self.h.iter().enumerate() {
+      digest[i * 4..(i + 1) * 4].copy_from_slice(&h.to_be_bytes());
+    }
+
+    digest
+  }
+
+  fn compress(&mut self, block: &[u8; 64]) {
+    let mut w = [0u32; 64];
+    for i in 0..16 {
+      w[i] = u32::from_be_bytes([
+        block[i * 4],
+        block[i * 4 + 1],
+        block[i * 4 + 2],
+        block[i * 4 + 3],
+      ]);
+    }
+    for i in 16..64 {
+      let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+      let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+      w[i] = w[i - 16]
+        .wrapping_add(s0)
+        .wrapping_add(w[i - 7])
+        .wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.h;
+    for (k, wi) in K.iter().zip(w) {
+      let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+      let ch = (e & f) ^ (!e & g);
+      let t1 = h
+        .wrapping_add(s1)
+        .wrapping_add(ch)
+        .wrapping_add(*k)
+        .wrapping_add(wi);
+      let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+      let maj = (a & b) ^ (a & c) ^ (b & c);
+      let t2 = s0.wrapping_add(maj);
+
+      h = g;
+      g = f;
+      f = e;
+      e = d.wrapping_add(t1);
+      d = c;
+      c = b;
+      b = a;
+      a = t1.wrapping_add(t2);
+    }
+
+    for (h, v) in self.h.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+      *h = h.wrapping_add(v);
+    }
+  }
+}
+
+// hmac authenticates message with HMAC-SHA256.
+pub fn hmac(key: &[u8], message: &[u8]) -> [u8; 32] {
+  let mut mac = [0u8; 32];
+  mac.copy_from_slice(&hash::hmac(
+    &|bb| Vec::from(Sha256::digest(bb)),
+    key,
+    message,
+  ));
+
+  mac
+}
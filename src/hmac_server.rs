@@ -0,0 +1,142 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::hash::sha1;
+use crate::{from_hex, to_hex};
+
+// Server answers GET /test?file=..&signature=.. on a local port, checking the hex signature against
+// HMAC-SHA1(key, file) one byte at a time, sleeping delay per byte and stopping at the first
+// mismatch. It answers 200 for a valid signature and 500 otherwise.
+pub struct Server {
+  addr: SocketAddr,
+  stop: Arc<AtomicBool>,
+  handle: Option<thread::JoinHandle<()>>,
+}
+
+impl Server {
+  pub fn start(key: &[u8], delay: Duration) -> io::Result<Server> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let key = Vec::from(key);
+    let stopped = Arc::clone(&stop);
+    let handle = thread::spawn(move || {
+      for stream in listener.incoming() {
+        if stopped.load(Ordering::SeqCst) {
+          break;
+        }
+        if let Ok(stream) = stream {
+          // A client hanging up early is no concern of the server's.
+          let _ = serve(stream, &key, delay);
+        }
+      }
+    });
+
+    Ok(Server {
+      addr,
+      stop,
+      handle: Some(handle),
+    })
+  }
+
+  pub fn addr(&self) -> SocketAddr {
+    self.addr
+  }
+}
+
+impl Drop for Server {
+  fn drop(&mut self) {
+    self.stop.store(true, Ordering::SeqCst);
+    // Wake the listener so it notices.
+    let _ = TcpStream::connect(self.addr);
+    if let Some(handle) = self.handle.take() {
+      let _ = handle.join();
+    }
+  }
+}
+
+fn serve(stream: TcpStream, key: &[u8], delay: Duration) -> io::Result<()> {
+  let mut reader = BufReader::new(&stream);
+  let mut line = String::new();
+  reader.read_line(&mut line)?;
+
+  // Read the headers through, so closing the connection doesn't reset it under the client.
+  let mut header = String::new();
+  while reader.read_line(&mut header)? > 2 {
+    header.clear();
+  }
+
+  let status = match parse(&line) {
+    Some((file, signature)) => {
+      if insecure_compare(&sha1::hmac(key, file.as_bytes()), &signature, delay) {
+        "200 OK"
+      } else {
+        "500 Internal Server Error"
+      }
+    }
+    None => "400 Bad Request",
+  };
+
+  write!(
+    &stream,
+    "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+    status
+  )
+}
+
+// parse pulls the file and decoded signature out of a request line for /test.
+fn parse(line: &str) -> Option<(String, Vec<u8>)> {
+  let target = line.split_whitespace().nth(1)?;
+  let query = target.strip_prefix("/test?")?;
+
+  let mut file = None;
+  let mut signature = None;
+  for kv in query.split('&') {
+    match kv.split_once('=') {
+      Some(("file", v)) => file = Some(v.to_string()),
+      Some(("signature", v)) => signature = from_hex(v),
+      _ => {}
+    }
+  }
+
+  Some((file?, signature?))
+}
+
+// insecure_compare compares a and b a byte at a time, sleeping delay per byte and bailing out at the
+// first difference, so that how long it takes gives away how much of b is right.
+pub fn insecure_compare(a: &[u8], b: &[u8], delay: Duration) -> bool {
+  if a.len() != b.len() {
+    return false;
+  }
+
+  for (x, y) in a.iter().zip(b) {
+    if x != y {
+      return false;
+    }
+    thread::sleep(delay);
+  }
+
+  true
+}
+
+// request asks the server at addr whether signature is valid for file.
+pub fn request(addr: SocketAddr, file: &str, signature: &[u8]) -> io::Result<bool> {
+  let mut stream = TcpStream::connect(addr)?;
+  let req = format!(
+    "GET /test?file={}&signature={} HTTP/1.1\r\nHost: {}\r\n\r\n",
+    file,
+    to_hex(signature),
+    addr
+  );
+  stream.write_all(req.as_bytes())?;
+
+  let mut line = String::new();
+  BufReader::new(&stream).read_line(&mut line)?;
+
+  Ok(line.split_whitespace().nth(1) == Some("200"))
+}
@@ -1,13 +1,25 @@
-use base64;
 use hex::{FromHex, ToHex};
 use rand::{rngs::OsRng, RngCore};
 use std::collections;
 
 use crate::aes::Encrypter;
 
+pub mod actor;
 pub mod aes;
 pub mod cbc;
+pub mod ctr;
+pub mod dh;
+pub mod dh_echo;
+pub mod dsa;
 pub mod ecb;
+pub mod hash;
+pub mod hmac_server;
+pub mod mt19937;
+pub mod mt_seed;
+pub mod padding_oracle;
+pub mod rsa;
+pub mod srp;
+pub mod timing;
 
 pub fn from_hex(h: &str) -> Option<Vec<u8>> {
     Vec::<u8>::from_hex(h).ok()
@@ -65,7 +77,7 @@ impl ScoredXOR {
         };
 
         for i in 0..=255 {
-            let res = xor(&m, &vec![i]);
+            let res = xor(m, &[i]);
             match String::from_utf8(res) {
                 Ok(r) => {
                     let char_counts = count(r.as_bytes(), b" eEtTAINOSainos");
@@ -99,8 +111,8 @@ fn best_keysize(bb: &[u8]) -> usize {
     let mut best: usize = 0;
     let mut lowest: f32 = 8.;
     for i in 2..=40 {
-        let a = &bb[0 * i..1 * i];
-        let b = &bb[1 * i..2 * i];
+        let a = &bb[..i];
+        let b = &bb[i..2 * i];
         let c = &bb[2 * i..3 * i];
         let d = &bb[3 * i..4 * i];
 
@@ -122,7 +134,7 @@ fn best_keysize(bb: &[u8]) -> usize {
 }
 
 pub fn find_key(bb: &[u8]) -> Vec<u8> {
-    let k = best_keysize(&bb);
+    let k = best_keysize(bb);
 
     let mut key = Vec::<u8>::new();
 
@@ -139,6 +151,35 @@ pub fn find_key(bb: &[u8]) -> Vec<u8> {
     key
 }
 
+// first_difference returns the index of the first n-byte block where a and b differ.
+fn first_difference(a: &[u8], b: &[u8], n: usize) -> usize {
+    a.chunks(n)
+        .zip(b.chunks(n))
+        .take_while(|(x, y)| x == y)
+        .count()
+}
+
+// prefix_len finds the length of the unknown prefix an encrypter puts before its input, for any
+// cipher that encrypts each block of n bytes depending only on what precedes it (n = 1 for
+// stream ciphers).
+pub fn prefix_len(encrypter: &dyn aes::Encrypter, n: usize) -> usize {
+    // Changing the first input byte changes the block the prefix ends in, and none before it.
+    let i = first_difference(&encrypter.encrypt(&[0]), &encrypter.encrypt(&[1]), n);
+
+    // Pushing the changed byte along with k bytes of filler leaves that block alone once the
+    // filler completes it.
+    for k in 1..n {
+        let mut a = vec![0; k + 1];
+        let e = encrypter.encrypt(&a);
+        a[k] = 1;
+        if first_difference(&e, &encrypter.encrypt(&a), n) > i {
+            return n * (i + 1) - k;
+        }
+    }
+
+    n * i
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Mode {
     ECB,
@@ -151,7 +192,7 @@ pub fn detect_block_mode(input: &[u8]) -> Mode {
         let k = &input[16 * i..16 * (i + 1)];
         *counts.entry(k).or_default() += 1;
     }
-    for (_, v) in &counts {
+    for v in counts.values() {
         if *v > 1 {
             return Mode::CBC;
         }
@@ -167,20 +208,24 @@ pub struct RandomEncrypter {
 
 impl RandomEncrypter {
     pub fn new(input: &[u8]) -> Result<RandomEncrypter, &str> {
+        RandomEncrypter::from_rng(&mut OsRng, input)
+    }
+
+    pub fn from_rng<'a>(rng: &mut dyn RngCore, input: &[u8]) -> Result<RandomEncrypter, &'a str> {
         let mut key = [0u8; 16];
-        OsRng.fill_bytes(&mut key);
+        rng.fill_bytes(&mut key);
 
-        let ecb = OsRng.next_u32() & 1 == 1;
-        let n_pad_before = (OsRng.next_u32() % 6 + 5) as usize; // random ∈ [5, 10]
-        let n_pad_after = (OsRng.next_u32() % 6 + 5) as usize; // random ∈ [5, 10]
+        let ecb = rng.next_u32() & 1 == 1;
+        let n_pad_before = (rng.next_u32() % 6 + 5) as usize; // random ∈ [5, 10]
+        let n_pad_after = (rng.next_u32() % 6 + 5) as usize; // random ∈ [5, 10]
 
         let mut plain = vec![0; n_pad_before];
-        OsRng.fill_bytes(&mut plain);
+        rng.fill_bytes(&mut plain);
 
         plain.extend(input);
 
         let mut suffix = vec![0; n_pad_after];
-        OsRng.fill_bytes(&mut suffix);
+        rng.fill_bytes(&mut suffix);
 
         plain.extend(suffix);
 
@@ -192,7 +237,7 @@ impl RandomEncrypter {
             })
         } else {
             let mut iv = [0u8; 16];
-            OsRng.fill_bytes(&mut iv);
+            rng.fill_bytes(&mut iv);
 
             Ok(RandomEncrypter {
                 mode: Mode::CBC,
@@ -210,8 +255,12 @@ pub struct RandomKeyCoder<T> {
 
 impl<T> RandomKeyCoder<T> {
     pub fn new(ctor: &dyn Fn(&[u8]) -> T) -> RandomKeyCoder<T> {
+        RandomKeyCoder::from_rng(&mut OsRng, ctor)
+    }
+
+    pub fn from_rng(rng: &mut dyn RngCore, ctor: &dyn Fn(&[u8]) -> T) -> RandomKeyCoder<T> {
         let mut key = [0u8; 16];
-        OsRng.fill_bytes(&mut key);
+        rng.fill_bytes(&mut key);
 
         RandomKeyCoder {
             coder: ctor(&key),
@@ -221,9 +270,13 @@ impl<T> RandomKeyCoder<T> {
     }
 
     pub fn with_random_prefix(self) -> RandomKeyCoder<T> {
-        let l = (OsRng.next_u32() % 256) as usize;
+        self.with_random_prefix_from_rng(&mut OsRng)
+    }
+
+    pub fn with_random_prefix_from_rng(self, rng: &mut dyn RngCore) -> RandomKeyCoder<T> {
+        let l = (rng.next_u32() % 256) as usize;
         let mut prefix = vec![0; l];
-        OsRng.fill_bytes(&mut prefix);
+        rng.fill_bytes(&mut prefix);
 
         self.with_prefix(prefix)
     }
@@ -248,7 +301,7 @@ impl<T: aes::Encrypter> aes::Encrypter for RandomKeyCoder<T> {
 }
 
 impl<T: aes::Decrypter> aes::Decrypter for RandomKeyCoder<T> {
-    fn decrypt(&self, plain: &[u8]) -> Result<Vec<u8>, &str> {
+    fn decrypt(&self, plain: &[u8]) -> Result<Vec<u8>, aes::Error> {
         let mut bb = self.coder.decrypt(plain)?;
         {
             let l = bb.len();
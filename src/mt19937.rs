@@ -0,0 +1,340 @@
+use rand::{Error, RngCore, SeedableRng};
+
+use crate::aes;
+
+const N: usize = 624;
+const M: usize = 397;
+const MATRIX_A: u32 = 0x9908b0df;
+const UPPER_MASK: u32 = 0x80000000;
+const LOWER_MASK: u32 = 0x7fffffff;
+
+// Mt19937 is the 32-bit Mersenne Twister, matching the reference mt19937ar.c.
+pub struct Mt19937 {
+  mt: [u32; N],
+  index: usize,
+}
+
+impl Mt19937 {
+  pub fn new(seed: u32) -> Mt19937 {
+    let mut mt = [0u32; N];
+    mt[0] = seed;
+    for i in 1..N {
+      mt[i] = 1812433253u32
+        .wrapping_mul(mt[i - 1] ^ (mt[i - 1] >> 30))
+        .wrapping_add(i as u32);
+    }
+
+    Mt19937 { mt, index: N }
+  }
+
+  // from_array seeds the generator from a key of any length, as init_by_array does.
+  pub fn from_array(key: &[u32]) -> Mt19937 {
+    assert!(!key.is_empty());
+
+    let mut r = Mt19937::new(19650218);
+    let mt = &mut r.mt;
+
+    let mut i = 1;
+    let mut j = 0;
+    for _ in 0..N.max(key.len()) {
+      mt[i] = (mt[i] ^ (mt[i - 1] ^ (mt[i - 1] >> 30)).wrapping_mul(1664525))
+        .wrapping_add(key[j])
+        .wrapping_add(j as u32);
+      i += 1;
+      j += 1;
+      if i >= N {
+        mt[0] = mt[N - 1];
+        i = 1;
+      }
+      if j >= key.len() {
+        j = 0;
+      }
+    }
+
+    for _ in 0..N - 1 {
+      mt[i] =
+        (mt[i] ^ (mt[i - 1] ^ (mt[i - 1] >> 30)).wrapping_mul(1566083941)).wrapping_sub(i as u32);
+      i += 1;
+      if i >= N {
+        mt[0] = mt[N - 1];
+        i = 1;
+      }
+    }
+
+    mt[0] = 0x80000000;
+
+    r
+  }
+
+  fn twist(&mut self) {
+    for i in 0..N {
+      let y = (self.mt[i] & UPPER_MASK) | (self.mt[(i + 1) % N] & LOWER_MASK);
+      let mut v = self.mt[(i + M) % N] ^ (y >> 1);
+      if y & 1 == 1 {
+        v ^= MATRIX_A;
+      }
+      self.mt[i] = v;
+    }
+
+    self.index = 0;
+  }
+}
+
+pub fn temper(mut y: u32) -> u32 {
+  y ^= y >> 11;
+  y ^= (y << 7) & 0x9d2c5680;
+  y ^= (y << 15) & 0xefc60000;
+  y ^= y >> 18;
+
+  y
+}
+
+// untemper inverts temper, recovering a state word from a single output.
+pub fn untemper(mut y: u32) -> u32 {
+  y = undo_right_shift(y, 18);
+  y = undo_left_shift(y, 15, 0xefc60000);
+  y = undo_left_shift(y, 7, 0x9d2c5680);
+  undo_right_shift(y, 11)
+}
+
+// undo_right_shift inverts y ^= y >> shift. Each pass fixes another shift bits from the top down.
+fn undo_right_shift(y: u32, shift: u32) -> u32 {
+  let mut x = y;
+  for _ in 0..32 / shift {
+    x = y ^ (x >> shift);
+  }
+
+  x
+}
+
+// undo_left_shift inverts y ^= (y << shift) & mask, fixing shift bits per pass from the bottom up.
+fn undo_left_shift(y: u32, shift: u32, mask: u32) -> u32 {
+  let mut x = y;
+  for _ in 0..32 / shift {
+    x = y ^ ((x << shift) & mask);
+  }
+
+  x
+}
+
+// clone_from_outputs rebuilds a generator from 624 consecutive outputs, starting at a twist
+// boundary, and fast-forwards it past any further outputs given, so that it predicts what the
+// original generator produces next.
+pub fn clone_from_outputs(outputs: &[u32]) -> Mt19937 {
+  assert!(outputs.len() >= N);
+
+  let mut mt = [0u32; N];
+  for (i, y) in outputs[..N].iter().enumerate() {
+    mt[i] = untemper(*y);
+  }
+
+  let mut r = Mt19937 { mt, index: N };
+  for _ in N..outputs.len() {
+    r.next_u32();
+  }
+
+  r
+}
+
+impl RngCore for Mt19937 {
+  fn next_u32(&mut self) -> u32 {
+    if self.index >= N {
+      self.twist();
+    }
+
+    let y = self.mt[self.index];
+    self.index += 1;
+
+    temper(y)
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    let lo = self.next_u32() as u64;
+    let hi = self.next_u32() as u64;
+
+    (hi << 32) | lo
+  }
+
+  fn fill_bytes(&mut self, dest: &mut [u8]) {
+    for chunk in dest.chunks_mut(4) {
+      let bb = self.next_u32().to_le_bytes();
+      chunk.copy_from_slice(&bb[..chunk.len()]);
+    }
+  }
+
+  fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+    self.fill_bytes(dest);
+    Ok(())
+  }
+}
+
+impl SeedableRng for Mt19937 {
+  type Seed = [u8; 4];
+
+  fn from_seed(seed: [u8; 4]) -> Mt19937 {
+    Mt19937::new(u32::from_le_bytes(seed))
+  }
+
+  // seed_from_u64 seeds with the low 32 bits, so that seeds agree with Mt19937::new.
+  fn seed_from_u64(state: u64) -> Mt19937 {
+    Mt19937::new(state as u32)
+  }
+}
+
+// StreamCipher xors its input with bytes drawn from an Mt19937 seeded with a 16-bit key.
+pub struct StreamCipher {
+  seed: u16,
+}
+
+impl StreamCipher {
+  pub fn new(seed: u16) -> StreamCipher {
+    StreamCipher { seed }
+  }
+
+  fn apply(&self, input: &[u8]) -> Vec<u8> {
+    let mut ks = vec![0; input.len()];
+    Mt19937::new(self.seed as u32).fill_bytes(&mut ks);
+
+    crate::xor(input, &ks)
+  }
+}
+
+impl aes::Encrypter for StreamCipher {
+  fn encrypt(&self, plain: &[u8]) -> Vec<u8> {
+    self.apply(plain)
+  }
+}
+
+impl aes::Decrypter for StreamCipher {
+  fn decrypt(&self, enc: &[u8]) -> Result<Vec<u8>, aes::Error> {
+    Ok(self.apply(enc))
+  }
+}
+
+const NN: usize = 312;
+const MM: usize = 156;
+const MATRIX_A_64: u64 = 0xb5026f5aa96619e9;
+const UPPER_MASK_64: u64 = 0xffffffff80000000;
+const LOWER_MASK_64: u64 = 0x7fffffff;
+
+// Mt19937_64 is the 64-bit Mersenne Twister, matching the reference mt19937-64.c.
+pub struct Mt19937_64 {
+  mt: [u64; NN],
+  index: usize,
+}
+
+impl Mt19937_64 {
+  pub fn new(seed: u64) -> Mt19937_64 {
+    let mut mt = [0u64; NN];
+    mt[0] = seed;
+    for i in 1..NN {
+      mt[i] = 6364136223846793005u64
+        .wrapping_mul(mt[i - 1] ^ (mt[i - 1] >> 62))
+        .wrapping_add(i as u64);
+    }
+
+    Mt19937_64 { mt, index: NN }
+  }
+
+  // from_array seeds the generator from a key of any length, as init_by_array64 does.
+  pub fn from_array(key: &[u64]) -> Mt19937_64 {
+    assert!(!key.is_empty());
+
+    let mut r = Mt19937_64::new(19650218);
+    let mt = &mut r.mt;
+
+    let mut i = 1;
+    let mut j = 0;
+    for _ in 0..NN.max(key.len()) {
+      mt[i] = (mt[i] ^ (mt[i - 1] ^ (mt[i - 1] >> 62)).wrapping_mul(3935559000370003845))
+        .wrapping_add(key[j])
+        .wrapping_add(j as u64);
+      i += 1;
+      j += 1;
+      if i >= NN {
+        mt[0] = mt[NN - 1];
+        i = 1;
+      }
+      if j >= key.len() {
+        j = 0;
+      }
+    }
+
+    for _ in 0..NN - 1 {
+      mt[i] = (mt[i] ^ (mt[i - 1] ^ (mt[i - 1] >> 62)).wrapping_mul(2862933555777941757))
+        .wrapping_sub(i as u64);
+      i += 1;
+      if i >= NN {
+        mt[0] = mt[NN - 1];
+        i = 1;
+      }
+    }
+
+    mt[0] = 1 << 63;
+
+    r
+  }
+
+  fn twist(&mut self) {
+    for i in 0..NN {
+      let x = (self.mt[i] & UPPER_MASK_64) | (self.mt[(i + 1) % NN] & LOWER_MASK_64);
+      let mut v = self.mt[(i + MM) % NN] ^ (x >> 1);
+      if x & 1 == 1 {
+        v ^= MATRIX_A_64;
+      }
+      self.mt[i] = v;
+    }
+
+    self.index = 0;
+  }
+}
+
+pub fn temper_64(mut x: u64) -> u64 {
+  x ^= (x >> 29) & 0x5555555555555555;
+  x ^= (x << 17) & 0x71d67fffeda60000;
+  x ^= (x << 37) & 0xfff7eee000000000;
+  x ^= x >> 43;
+
+  x
+}
+
+impl RngCore for Mt19937_64 {
+  fn next_u32(&mut self) -> u32 {
+    self.next_u64() as u32
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    if self.index >= NN {
+      self.twist();
+    }
+
+    let x = self.mt[self.index];
+    self.index += 1;
+
+    temper_64(x)
+  }
+
+  fn fill_bytes(&mut self, dest: &mut [u8]) {
+    for chunk in dest.chunks_mut(8) {
+      let bb = self.next_u64().to_le_bytes();
+      chunk.copy_from_slice(&bb[..chunk.len()]);
+    }
+  }
+
+  fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+    self.fill_bytes(dest);
+    Ok(())
+  }
+}
+
+impl SeedableRng for Mt19937_64 {
+  type Seed = [u8; 8];
+
+  fn from_seed(seed: [u8; 8]) -> Mt19937_64 {
+    Mt19937_64::new(u64::from_le_bytes(seed))
+  }
+
+  fn seed_from_u64(state: u64) -> Mt19937_64 {
+    Mt19937_64::new(state)
+  }
+}
@@ -0,0 +1,61 @@
+use rand::RngCore;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::aes::Decrypter;
+use crate::mt19937::{Mt19937, StreamCipher};
+
+// Clock tells the time in whole seconds since the Unix epoch.
+pub trait Clock {
+  fn now(&self) -> u32;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+  fn now(&self) -> u32 {
+    SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|d| d.as_secs() as u32)
+      .unwrap_or(0)
+  }
+}
+
+// crack_time_seed finds the seed of a generator seeded with the time at most window seconds ago,
+// given its first output.
+pub fn crack_time_seed(first: u32, clock: &dyn Clock, window: u32) -> Option<u32> {
+  let now = clock.now();
+
+  (now.saturating_sub(window)..=now)
+    .rev()
+    .find(|seed| Mt19937::new(*seed).next_u32() == first)
+}
+
+// crack_stream_seed finds the 16-bit seed of a StreamCipher ciphertext whose plaintext ends with
+// known.
+pub fn crack_stream_seed(ciphertext: &[u8], known: &[u8]) -> Option<u16> {
+  (0..=u16::MAX).find(|seed| match StreamCipher::new(*seed).decrypt(ciphertext) {
+    Ok(plain) => plain.ends_with(known),
+    Err(_) => false,
+  })
+}
+
+// reset_token makes a 16 byte password reset token from a generator seeded with the current time.
+pub fn reset_token(clock: &dyn Clock) -> Vec<u8> {
+  let mut token = vec![0; 16];
+  Mt19937::new(clock.now()).fill_bytes(&mut token);
+
+  token
+}
+
+// is_time_seeded_token reports whether token could have come from reset_token at some point in the
+// last window seconds.
+pub fn is_time_seeded_token(token: &[u8], clock: &dyn Clock, window: u32) -> bool {
+  let now = clock.now();
+
+  (now.saturating_sub(window)..=now).rev().any(|seed| {
+    let mut bb = vec![0; token.len()];
+    Mt19937::new(seed).fill_bytes(&mut bb);
+
+    bb == token
+  })
+}
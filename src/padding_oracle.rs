@@ -0,0 +1,87 @@
+use crate::aes;
+
+pub struct Decrypter<'a, F: Fn(&[u8], &[u8]) -> bool> {
+  oracle: &'a F,
+}
+
+impl<'a, F: Fn(&[u8], &[u8]) -> bool> Decrypter<'a, F> {
+  // new wraps an oracle reporting whether the CBC decryption of (iv, ciphertext) has valid padding.
+  pub fn new(oracle: &F) -> Decrypter<'_, F> {
+    Decrypter { oracle }
+  }
+
+  // decrypt recovers the plaintext of a CBC ciphertext from padding verdicts alone.
+  pub fn decrypt(&self, iv: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, &str> {
+    if iv.len() != 16 {
+      return Err("iv must be 16 bytes long");
+    }
+    if !ciphertext.len().is_multiple_of(16) {
+      return Err("ciphertext must be a whole number of blocks");
+    }
+
+    let mut plain = Vec::<u8>::new();
+    let mut prev = iv;
+    for block in ciphertext.chunks(16) {
+      let intermediate = self.intermediate(block)?;
+      for i in 0..16 {
+        plain.push(intermediate[i] ^ prev[i]);
+      }
+      prev = block;
+    }
+
+    aes::unpad(plain)
+  }
+
+  // intermediate finds the raw block decryption of block, before it is xored with the previous
+  // ciphertext block, by forging the previous block so that the tail decrypts to padding.
+  fn intermediate(&self, block: &[u8]) -> Result<[u8; 16], &str> {
+    let mut intermediate = [0u8; 16];
+    intermediate[15] = self.last_byte(block)?;
+
+    for k in (0..15).rev() {
+      let pad = (16 - k) as u8;
+      let mut forged = [0u8; 16];
+      for j in k + 1..16 {
+        forged[j] = intermediate[j] ^ pad;
+      }
+
+      intermediate[k] = self.scan(&mut forged, k, block)? ^ pad;
+    }
+
+    Ok(intermediate)
+  }
+
+  // scan returns the value of forged[k] for which the oracle accepts the padding.
+  fn scan(&self, forged: &mut [u8; 16], k: usize, block: &[u8]) -> Result<u8, &str> {
+    for i in 0..=255 {
+      forged[k] = i;
+      if (self.oracle)(forged, block) {
+        return Ok(i);
+      }
+    }
+
+    Err("no forged byte gave valid padding")
+  }
+
+  // last_byte finds the final intermediate byte as the forged byte that decrypts to a one byte
+  // pad. An accepted guess might instead have hit a longer pad such as 0x02 0x02, so it is probed
+  // again with the second to last byte changed, which only a one byte pad survives.
+  fn last_byte(&self, block: &[u8]) -> Result<u8, &str> {
+    let mut forged = [0u8; 16];
+    for i in 0..=255 {
+      forged[15] = i;
+      if !(self.oracle)(&forged, block) {
+        continue;
+      }
+
+      forged[14] ^= 1;
+      let single = (self.oracle)(&forged, block);
+      forged[14] ^= 1;
+      if single {
+        return Ok(i ^ 1);
+      }
+    }
+
+    Err("no forged byte gave valid padding")
+  }
+}
@@ -0,0 +1,460 @@
+use num_bigint::{BigInt, BigUint, RandBigInt};
+use num_integer::Integer;
+use num_rational::BigRational;
+use num_traits::{One, Zero};
+use rand::rngs::OsRng;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::convert::TryFrom;
+
+use crate::hash::sha1::Sha1;
+use crate::{from_hex, to_hex};
+
+// to_int reads bb as a big endian integer.
+pub fn to_int(bb: &[u8]) -> BigUint {
+  BigUint::from_bytes_be(bb)
+}
+
+// from_int writes n as big endian bytes, with no leading zeros.
+pub fn from_int(n: &BigUint) -> Vec<u8> {
+  if n.is_zero() {
+    return Vec::new();
+  }
+
+  n.to_bytes_be()
+}
+
+pub fn int_from_hex(s: &str) -> Option<BigUint> {
+  from_hex(s).map(|bb| to_int(&bb))
+}
+
+pub fn int_to_hex(n: &BigUint) -> String {
+  to_hex(&from_int(n))
+}
+
+// egcd returns (g, x, y) such that a * x + b * y = g = gcd(a, b).
+pub fn egcd(a: &BigInt, b: &BigInt) -> (BigInt, BigInt, BigInt) {
+  let (mut r0, mut r1) = (a.clone(), b.clone());
+  let (mut x0, mut x1) = (BigInt::one(), BigInt::zero());
+  let (mut y0, mut y1) = (BigInt::zero(), BigInt::one());
+
+  while !r1.is_zero() {
+    let q = &r0 / &r1;
+    let r = &r0 - &q * &r1;
+    r0 = std::mem::replace(&mut r1, r);
+    let x = &x0 - &q * &x1;
+    x0 = std::mem::replace(&mut x1, x);
+    let y = &y0 - &q * &y1;
+    y0 = std::mem::replace(&mut y1, y);
+  }
+
+  (r0, x0, y0)
+}
+
+// invmod returns the inverse of a mod m, if a and m are coprime.
+pub fn invmod(a: &BigUint, m: &BigUint) -> Option<BigUint> {
+  let m = BigInt::from(m.clone());
+  let (g, x, _) = egcd(&BigInt::from(a.clone()), &m);
+  if !g.is_one() {
+    return None;
+  }
+
+  x.mod_floor(&m).to_biguint()
+}
+
+const SMALL_PRIMES: [u32; 15] = [3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53];
+
+// is_probable_prime runs rounds of Miller-Rabin with random bases, after weeding out small factors.
+pub fn is_probable_prime(n: &BigUint, rounds: usize) -> bool {
+  let two = BigUint::from(2u32);
+  if *n < two {
+    return false;
+  }
+  for p in SMALL_PRIMES
+    .iter()
+    .map(|p| BigUint::from(*p))
+    .chain([two.clone()])
+  {
+    if *n == p {
+      return true;
+    }
+    if (n % &p).is_zero() {
+      return false;
+    }
+  }
+
+  // n - 1 = d * 2^s with d odd.
+  let minus_one = n - 1u32;
+  let s = minus_one.trailing_zeros().unwrap_or(0);
+  let d = &minus_one >> s;
+
+  'witness: for _ in 0..rounds {
+    let a = OsRng.gen_biguint_range(&two, &minus_one);
+    let mut x = a.modpow(&d, n);
+    if x.is_one() || x == minus_one {
+      continue;
+    }
+    for _ in 1..s {
+      x = x.modpow(&two, n);
+      if x == minus_one {
+        continue 'witness;
+      }
+    }
+
+    return false;
+  }
+
+  true
+}
+
+// gen_prime returns a random prime of exactly bits bits. There are none below 2 bits.
+pub fn gen_prime(bits: u64) -> Result<BigUint, &'static str> {
+  if bits < 2 {
+    return Err("primes need at least 2 bits");
+  }
+
+  loop {
+    let mut p = OsRng.gen_biguint(bits);
+    p.set_bit(bits - 1, true);
+    p.set_bit(0, true);
+    if is_probable_prime(&p, 40) {
+      return Ok(p);
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PublicKey {
+  pub e: BigUint,
+  pub n: BigUint,
+}
+
+impl PublicKey {
+  pub fn encrypt(&self, m: &BigUint) -> BigUint {
+    m.modpow(&self.e, &self.n)
+  }
+
+  // size is the length of the modulus in bytes.
+  pub fn size(&self) -> usize {
+    self.n.bits().div_ceil(8) as usize
+  }
+
+  // verify checks a PKCS#1 v1.5 SHA-1 signature on message. A sloppy verifier parses the block
+  // from the left and stops once it has read the hash, so it never notices anything after it; a
+  // strict one rebuilds the whole block and compares.
+  pub fn verify(&self, message: &[u8], signature: &BigUint, strict: bool) -> bool {
+    let bb = from_int(&self.encrypt(signature));
+    let k = self.size();
+    if bb.len() > k {
+      return false;
+    }
+    let mut block = vec![0; k - bb.len()];
+    block.extend(bb);
+
+    if strict {
+      return pkcs1_block(k, message).is_ok_and(|b| b == block);
+    }
+
+    if !block.starts_with(&[0, 1, 0xff]) {
+      return false;
+    }
+    let rest = &block[2..];
+    let rest = match rest.iter().position(|b| *b != 0xff) {
+      Some(i) if rest[i] == 0 => &rest[i + 1..],
+      _ => return false,
+    };
+
+    match rest.strip_prefix(&SHA1_DIGEST_INFO[..]) {
+      Some(hash) => hash.len() >= 20 && hash[..20] == Sha1::digest(message),
+      None => false,
+    }
+  }
+}
+
+// RSA is a key pair for textbook RSA, with no padding of any kind.
+pub struct RSA {
+  public: PublicKey,
+  d: BigUint,
+}
+
+impl RSA {
+  // new generates a key with a modulus of bits bits and public exponent e, picking primes for
+  // which e is invertible.
+  pub fn new(bits: u64, e: u32) -> Result<RSA, &'static str> {
+    if bits < 16 {
+      return Err("modulus too small");
+    }
+    if e < 3 || e.is_even() {
+      return Err("public exponent must be odd and at least 3");
+    }
+
+    let e = BigUint::from(e);
+    loop {
+      let p = gen_prime(bits / 2)?;
+      let q = gen_prime(bits - bits / 2)?;
+      if p == q {
+        continue;
+      }
+
+      let n = &p * &q;
+      if n.bits() != bits {
+        continue;
+      }
+      let et = (p - 1u32) * (q - 1u32);
+      if let Some(d) = invmod(&e, &et) {
+        return Ok(RSA {
+          public: PublicKey { e, n },
+          d,
+        });
+      }
+    }
+  }
+
+  pub fn public(&self) -> &PublicKey {
+    &self.public
+  }
+
+  pub fn encrypt(&self, m: &BigUint) -> BigUint {
+    self.public.encrypt(m)
+  }
+
+  pub fn decrypt(&self, c: &BigUint) -> BigUint {
+    c.modpow(&self.d, &self.public.n)
+  }
+
+  // sign makes a PKCS#1 v1.5 signature over the SHA-1 hash of message.
+  pub fn sign(&self, message: &[u8]) -> Result<BigUint, &'static str> {
+    let block = pkcs1_block(self.public.size(), message)?;
+
+    Ok(self.decrypt(&to_int(&block)))
+  }
+}
+
+// SHA1_DIGEST_INFO is the DER prefix naming SHA-1 ahead of the hash in a PKCS#1 v1.5 signature.
+const SHA1_DIGEST_INFO: [u8; 15] = [
+  0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04, 0x14,
+];
+
+// pkcs1_block is the k byte block signed for message: 00 01, 0xff padding, 00, then the SHA-1
+// digest info.
+fn pkcs1_block(k: usize, message: &[u8]) -> Result<Vec<u8>, &'static str> {
+  let t = SHA1_DIGEST_INFO.len() + 20;
+  if k < t + 11 {
+    return Err("modulus too small to sign with");
+  }
+
+  let mut block = vec![0, 1];
+  block.resize(k - t - 1, 0xff);
+  block.push(0);
+  block.extend(SHA1_DIGEST_INFO);
+  block.extend(Sha1::digest(message));
+
+  Ok(block)
+}
+
+// forge_signature makes a signature on message that a sloppy verifier accepts under an e = 3 key,
+// without the private key. It builds a block with the shortest padding and the hash up front, and
+// fills the rest with 0xff. The cube root of that, rounded down, cubes to a number that still
+// starts with the same bytes, as long as the filler is long enough to absorb the rounding.
+pub fn forge_signature(key: &PublicKey, message: &[u8]) -> Result<BigUint, &'static str> {
+  if key.e != BigUint::from(3u32) {
+    return Err("forgery needs e = 3");
+  }
+
+  let mut prefix = vec![0, 1, 0xff, 0];
+  prefix.extend(SHA1_DIGEST_INFO);
+  prefix.extend(Sha1::digest(message));
+
+  let mut block = prefix.clone();
+  block.resize(key.size(), 0xff);
+  let signature = nth_root(&to_int(&block), 3);
+
+  let forged = from_int(&signature.pow(3));
+  if forged.len() + 1 != key.size() || !forged.starts_with(&prefix[1..]) {
+    return Err("modulus too small to forge under");
+  }
+
+  Ok(signature)
+}
+
+// crt finds the x below the product of moduli with x = residues[i] mod moduli[i] for every i, if
+// the moduli are pairwise coprime.
+pub fn crt(residues: &[BigUint], moduli: &[BigUint]) -> Option<BigUint> {
+  if residues.len() != moduli.len() {
+    return None;
+  }
+
+  let product: BigUint = moduli.iter().product();
+  let mut x = BigUint::zero();
+  for (r, m) in residues.iter().zip(moduli) {
+    let rest = &product / m;
+    x += r * &rest * invmod(&(&rest % m), m)?;
+  }
+
+  Some(x % product)
+}
+
+// nth_root is the largest integer whose nth power is at most x.
+pub fn nth_root(x: &BigUint, n: u32) -> BigUint {
+  x.nth_root(n)
+}
+
+// exact_nth_root is the nth root of x, if x is a perfect nth power.
+pub fn exact_nth_root(x: &BigUint, n: u32) -> Option<BigUint> {
+  let root = nth_root(x, n);
+  if root.pow(n) == *x {
+    Some(root)
+  } else {
+    None
+  }
+}
+
+// broadcast_attack recovers a message sent to e recipients whose keys share the small public
+// exponent e, from each key and the ciphertext sent under it. The ciphertexts combine by CRT into
+// m^e below the product of the moduli, which has an exact e-th root.
+pub fn broadcast_attack(captures: &[(PublicKey, BigUint)]) -> Result<BigUint, &'static str> {
+  let e = match captures.first() {
+    Some((key, _)) => key.e.clone(),
+    None => return Err("no ciphertexts"),
+  };
+  if captures.iter().any(|(key, _)| key.e != e) {
+    return Err("keys use different exponents");
+  }
+  let e = match u32::try_from(&e) {
+    Ok(e) if captures.len() >= e as usize => e,
+    _ => return Err("need as many ciphertexts as the exponent"),
+  };
+
+  let captures = &captures[..e as usize];
+  let residues: Vec<BigUint> = captures.iter().map(|(_, c)| c.clone()).collect();
+  let moduli: Vec<BigUint> = captures.iter().map(|(key, _)| key.n.clone()).collect();
+  let power = crt(&residues, &moduli).ok_or("moduli share a factor")?;
+
+  exact_nth_root(&power, e).ok_or("combined ciphertext is not a perfect power")
+}
+
+// Oracle is a service holding an RSA private key that answers some question about ciphertexts
+// under it. What it will answer is up to each kind of oracle.
+pub trait Oracle {
+  fn public(&self) -> &PublicKey;
+}
+
+// DecryptionOracle decrypts ciphertexts, on whatever terms it sets.
+pub trait DecryptionOracle: Oracle {
+  fn decrypt(&self, c: &BigUint) -> Result<BigUint, &'static str>;
+}
+
+// DecryptionServer decrypts any ciphertext it is sent, but only once, as if it kept the hashes of
+// everything it had seen.
+pub struct DecryptionServer {
+  key: RSA,
+  seen: RefCell<HashSet<BigUint>>,
+}
+
+impl DecryptionServer {
+  pub fn new(key: RSA) -> DecryptionServer {
+    DecryptionServer {
+      key,
+      seen: RefCell::new(HashSet::new()),
+    }
+  }
+}
+
+impl Oracle for DecryptionServer {
+  fn public(&self) -> &PublicKey {
+    self.key.public()
+  }
+}
+
+impl DecryptionOracle for DecryptionServer {
+  fn decrypt(&self, c: &BigUint) -> Result<BigUint, &'static str> {
+    if !self.seen.borrow_mut().insert(c.clone()) {
+      return Err("ciphertext already decrypted");
+    }
+
+    Ok(self.key.decrypt(c))
+  }
+}
+
+// blinding_attack decrypts c through an oracle that won't decrypt c itself. It asks for
+// s^e * c instead, which decrypts to s * m, and divides s back out.
+pub fn blinding_attack(
+  oracle: &dyn DecryptionOracle,
+  c: &BigUint,
+) -> Result<BigUint, &'static str> {
+  let key = oracle.public();
+  let (s, inverse) = loop {
+    let s = OsRng.gen_biguint_range(&BigUint::from(2u32), &key.n);
+    if let Some(inverse) = invmod(&s, &key.n) {
+      break (s, inverse);
+    }
+  };
+
+  let blinded = (key.encrypt(&s) * c) % &key.n;
+  let p = oracle.decrypt(&blinded)?;
+
+  Ok((p * inverse) % &key.n)
+}
+
+// ParityOracle reveals whether a ciphertext decrypts to an even number.
+pub trait ParityOracle: Oracle {
+  fn is_even(&self, c: &BigUint) -> bool;
+}
+
+// ParityServer answers parity queries with its private key, for anyone who asks.
+pub struct ParityServer {
+  key: RSA,
+}
+
+impl ParityServer {
+  pub fn new(key: RSA) -> ParityServer {
+    ParityServer { key }
+  }
+}
+
+impl Oracle for ParityServer {
+  fn public(&self) -> &PublicKey {
+    self.key.public()
+  }
+}
+
+impl ParityOracle for ParityServer {
+  fn is_even(&self, c: &BigUint) -> bool {
+    self.key.decrypt(c).is_even()
+  }
+}
+
+// parity_attack decrypts c with one parity query per bit of the modulus. Multiplying c by 2^e
+// doubles the plaintext, and since n is odd, 2m mod n is even exactly when 2m didn't wrap, that is
+// when m lies in the lower half of where it was known to be. The bounds are kept as exact
+// fractions, as rounding them to integers along the way gets the last bits wrong. If hollywood is
+// given, it is shown the upper bound after every step.
+pub fn parity_attack(
+  oracle: &dyn ParityOracle,
+  c: &BigUint,
+  mut hollywood: Option<&mut dyn FnMut(&BigUint)>,
+) -> BigUint {
+  let key = oracle.public();
+  let double = key.encrypt(&BigUint::from(2u32));
+
+  let mut lo = BigRational::zero();
+  let mut hi = BigRational::from_integer(BigInt::from(key.n.clone()));
+  let mut c = c.clone();
+  for _ in 0..key.n.bits() {
+    c = (c * &double) % &key.n;
+
+    let mid = (&lo + &hi) / BigInt::from(2);
+    if oracle.is_even(&c) {
+      hi = mid;
+    } else {
+      lo = mid;
+    }
+
+    if let Some(show) = hollywood.as_mut() {
+      show(&hi.floor().to_integer().magnitude().clone());
+    }
+  }
+
+  // The plaintext is the one integer in [lo, hi), now narrower than 1.
+  lo.ceil().to_integer().magnitude().clone()
+}
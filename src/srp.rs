@@ -0,0 +1,387 @@
+use num_bigint::{BigUint, RandBigInt};
+use rand::{rngs::OsRng, RngCore};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+
+use crate::actor::Endpoint;
+use crate::dh;
+use crate::hash::sha256::{self, Sha256};
+use crate::{from_hex, to_hex};
+
+pub mod simple;
+
+pub enum Message {
+  // Hello names the user logging in and carries the client's public key A.
+  Hello {
+    email: String,
+    public: BigUint,
+  },
+  // Challenge answers with the user's salt and the server's public key B.
+  Challenge {
+    salt: Vec<u8>,
+    public: BigUint,
+  },
+  // SimpleChallenge answers Hello in simplified SRP, which sends the scrambler u rather than
+  // deriving it from the public keys.
+  SimpleChallenge {
+    salt: Vec<u8>,
+    public: BigUint,
+    u: BigUint,
+  },
+  // Proof is HMAC-SHA256(K, salt), showing the client arrived at the same session key K.
+  Proof(Vec<u8>),
+  // Verdict tells the client whether the proof was accepted.
+  Verdict(bool),
+}
+
+impl Message {
+  // encode writes the message as a line of text, for sending over a stream.
+  pub fn encode(&self) -> String {
+    match self {
+      Message::Hello { email, public } => format!("hello {} {}\n", email, public.to_str_radix(16)),
+      Message::Challenge { salt, public } => {
+        format!("challenge {} {}\n", to_hex(salt), public.to_str_radix(16))
+      }
+      Message::SimpleChallenge { salt, public, u } => format!(
+        "simple-challenge {} {} {}\n",
+        to_hex(salt),
+        public.to_str_radix(16),
+        u.to_str_radix(16)
+      ),
+      Message::Proof(mac) => format!("proof {}\n", to_hex(mac)),
+      Message::Verdict(ok) => format!("verdict {}\n", if *ok { "ok" } else { "denied" }),
+    }
+  }
+
+  // decode reads a message written by encode.
+  pub fn decode(line: &str) -> Result<Message, &'static str> {
+    let int = |s: &str| BigUint::parse_bytes(s.as_bytes(), 16).ok_or("malformed number");
+    let bytes = |s: &str| from_hex(s).ok_or("malformed hex");
+
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    match fields.as_slice() {
+      ["hello", email, public] => Ok(Message::Hello {
+        email: email.to_string(),
+        public: int(public)?,
+      }),
+      ["challenge", salt, public] => Ok(Message::Challenge {
+        salt: bytes(salt)?,
+        public: int(public)?,
+      }),
+      ["simple-challenge", salt, public, u] => Ok(Message::SimpleChallenge {
+        salt: bytes(salt)?,
+        public: int(public)?,
+        u: int(u)?,
+      }),
+      ["proof", mac] => Ok(Message::Proof(bytes(mac)?)),
+      ["verdict", "ok"] => Ok(Message::Verdict(true)),
+      ["verdict", "denied"] => Ok(Message::Verdict(false)),
+      _ => Err("malformed message"),
+    }
+  }
+}
+
+// Transport carries messages between a client and a server.
+pub trait Transport {
+  fn send(&mut self, message: Message) -> Result<(), &'static str>;
+  fn recv(&mut self) -> Result<Message, &'static str>;
+}
+
+impl Transport for Endpoint<Message> {
+  fn send(&mut self, message: Message) -> Result<(), &'static str> {
+    Endpoint::send(self, message)
+  }
+
+  fn recv(&mut self) -> Result<Message, &'static str> {
+    Endpoint::recv(self)
+  }
+}
+
+// Stream carries messages over a TCP connection, one line each.
+pub struct Stream {
+  reader: BufReader<TcpStream>,
+}
+
+impl Stream {
+  pub fn new(stream: TcpStream) -> Stream {
+    Stream {
+      reader: BufReader::new(stream),
+    }
+  }
+}
+
+impl Transport for Stream {
+  fn send(&mut self, message: Message) -> Result<(), &'static str> {
+    self
+      .reader
+      .get_mut()
+      .write_all(message.encode().as_bytes())
+      .map_err(|_| "write failed")
+  }
+
+  fn recv(&mut self) -> Result<Message, &'static str> {
+    let mut line = String::new();
+    match self.reader.read_line(&mut line) {
+      Ok(0) | Err(_) => Err("connection closed"),
+      Ok(_) => Message::decode(&line),
+    }
+  }
+}
+
+// Login is the client side of an exchange.
+pub trait Login {
+  fn hello(&self) -> Message;
+  // respond answers the server's challenge with a proof of the session key.
+  fn respond(&mut self, challenge: Message) -> Result<Message, &'static str>;
+}
+
+// Verifier is the server side of an exchange.
+pub trait Verifier {
+  // challenge answers a client's hello.
+  fn challenge(&mut self, hello: Message) -> Result<Message, &'static str>;
+  // verify checks a client's proof, answering with a verdict.
+  fn verify(&mut self, proved: Message) -> Result<Message, &'static str>;
+}
+
+pub fn n() -> BigUint {
+  dh::nist_p()
+}
+
+pub fn g() -> BigUint {
+  dh::nist_g()
+}
+
+// hash_int is a SHA-256 digest read as a big endian integer.
+fn hash_int(parts: &[&[u8]]) -> BigUint {
+  let mut s = Sha256::new();
+  for p in parts {
+    s.update(p);
+  }
+
+  BigUint::from_bytes_be(&s.finalize())
+}
+
+// multiplier is the SRP-6a k = H(N, g), rather than the fixed k = 3 of SRP-6.
+fn multiplier() -> BigUint {
+  hash_int(&[&n().to_bytes_be(), &g().to_bytes_be()])
+}
+
+// scramble is u, binding the session to both public keys.
+fn scramble(a: &BigUint, b: &BigUint) -> BigUint {
+  hash_int(&[&a.to_bytes_be(), &b.to_bytes_be()])
+}
+
+// session_key derives K from the shared secret S.
+pub fn session_key(secret: &BigUint) -> [u8; 32] {
+  Sha256::digest(&secret.to_bytes_be())
+}
+
+// proof is what a client holding key sends to show it, given the user's salt.
+pub fn proof(key: &[u8], salt: &[u8]) -> Vec<u8> {
+  Vec::from(sha256::hmac(key, salt))
+}
+
+// Client logs in as email with password.
+pub struct Client {
+  email: String,
+  password: Vec<u8>,
+  a: BigUint,
+  public: BigUint,
+  key: Option<[u8; 32]>,
+}
+
+impl Client {
+  pub fn new(email: &str, password: &[u8]) -> Client {
+    let a = OsRng.gen_biguint_range(&BigUint::from(1u32), &n());
+    let public = g().modpow(&a, &n());
+
+    Client {
+      email: email.to_string(),
+      password: Vec::from(password),
+      a,
+      public,
+      key: None,
+    }
+  }
+
+  // key is the session key, once the challenge has been answered.
+  pub fn key(&self) -> Option<&[u8]> {
+    self.key.as_ref().map(|k| &k[..])
+  }
+}
+
+impl Login for Client {
+  fn hello(&self) -> Message {
+    Message::Hello {
+      email: self.email.clone(),
+      public: self.public.clone(),
+    }
+  }
+
+  // respond derives the session key from the server's challenge and proves it.
+  fn respond(&mut self, challenge: Message) -> Result<Message, &'static str> {
+    let (salt, b) = match challenge {
+      Message::Challenge { salt, public } => (salt, public),
+      _ => return Err("expected a challenge"),
+    };
+
+    let (n, g) = (n(), g());
+    let u = scramble(&self.public, &b);
+    let x = hash_int(&[&salt, &self.password]);
+
+    // S = (B - k * g^x)^(a + u * x), keeping the base positive mod N.
+    let kgx = (multiplier() * g.modpow(&x, &n)) % &n;
+    let base = (b + &n - kgx) % &n;
+    let secret = base.modpow(&(&self.a + u * x), &n);
+
+    let key = session_key(&secret);
+    self.key = Some(key);
+
+    Ok(Message::Proof(proof(&key, &salt)))
+  }
+}
+
+// Session is a login in progress on the server.
+struct Session {
+  public: BigUint,
+  b: BigUint,
+  other: BigUint,
+}
+
+// Server holds the verifier for a single registered user and checks their logins.
+pub struct Server {
+  email: String,
+  salt: Vec<u8>,
+  v: BigUint,
+  session: Option<Session>,
+  key: Option<[u8; 32]>,
+}
+
+impl Server {
+  // new registers email with password, keeping only a random salt and the verifier g^x.
+  pub fn new(email: &str, password: &[u8]) -> Server {
+    let mut salt = vec![0; 16];
+    OsRng.fill_bytes(&mut salt);
+    let x = hash_int(&[&salt, password]);
+
+    Server {
+      email: email.to_string(),
+      v: g().modpow(&x, &n()),
+      salt,
+      session: None,
+      key: None,
+    }
+  }
+
+  // key is the session key of the last successful login.
+  pub fn key(&self) -> Option<&[u8]> {
+    self.key.as_ref().map(|k| &k[..])
+  }
+}
+
+impl Verifier for Server {
+  // challenge starts a login, answering with the salt and B = k * v + g^b.
+  fn challenge(&mut self, hello: Message) -> Result<Message, &'static str> {
+    let (email, other) = match hello {
+      Message::Hello { email, public } => (email, public),
+      _ => return Err("expected hello"),
+    };
+    if email != self.email {
+      return Err("unknown user");
+    }
+
+    let n = n();
+    let b = OsRng.gen_biguint_range(&BigUint::from(1u32), &n);
+    let public = (multiplier() * &self.v + g().modpow(&b, &n)) % &n;
+    self.session = Some(Session {
+      public: public.clone(),
+      b,
+      other,
+    });
+
+    Ok(Message::Challenge {
+      salt: self.salt.clone(),
+      public,
+    })
+  }
+
+  // verify checks the client's proof against the session key, S = (A * v^u)^b.
+  fn verify(&mut self, proved: Message) -> Result<Message, &'static str> {
+    let mac = match proved {
+      Message::Proof(mac) => mac,
+      _ => return Err("expected a proof"),
+    };
+    let session = self.session.take().ok_or("no login in progress")?;
+
+    let n = n();
+    let u = scramble(&session.other, &session.public);
+    let secret = (session.other * self.v.modpow(&u, &n)).modpow(&session.b, &n);
+
+    let key = session_key(&secret);
+    let ok = mac == proof(&key, &self.salt);
+    if ok {
+      self.key = Some(key);
+    }
+
+    Ok(Message::Verdict(ok))
+  }
+}
+
+// login runs client through a login over link, reporting whether it was accepted.
+pub fn login(client: &mut dyn Login, link: &mut dyn Transport) -> Result<bool, &'static str> {
+  link.send(client.hello())?;
+  let proved = client.respond(link.recv()?)?;
+  link.send(proved)?;
+
+  match link.recv()? {
+    Message::Verdict(ok) => Ok(ok),
+    _ => Err("expected a verdict"),
+  }
+}
+
+// serve answers one login over link, reporting whether it was accepted.
+pub fn serve(server: &mut dyn Verifier, link: &mut dyn Transport) -> Result<bool, &'static str> {
+  let challenge = server.challenge(link.recv()?)?;
+  link.send(challenge)?;
+
+  let verdict = server.verify(link.recv()?)?;
+  let ok = matches!(verdict, Message::Verdict(true));
+  link.send(verdict)?;
+
+  Ok(ok)
+}
+
+// ZeroKeyClient logs in as email without the password, by sending a multiple of N as its public
+// key. The server's secret (A * v^u)^b is then 0, whatever the password.
+pub struct ZeroKeyClient {
+  email: String,
+  multiple: u32,
+}
+
+impl ZeroKeyClient {
+  pub fn new(email: &str, multiple: u32) -> ZeroKeyClient {
+    ZeroKeyClient {
+      email: email.to_string(),
+      multiple,
+    }
+  }
+}
+
+impl Login for ZeroKeyClient {
+  fn hello(&self) -> Message {
+    Message::Hello {
+      email: self.email.clone(),
+      public: n() * self.multiple,
+    }
+  }
+
+  fn respond(&mut self, challenge: Message) -> Result<Message, &'static str> {
+    match challenge {
+      Message::Challenge { salt, .. } => Ok(Message::Proof(proof(
+        &session_key(&BigUint::default()),
+        &salt,
+      ))),
+      _ => Err("expected a challenge"),
+    }
+  }
+}
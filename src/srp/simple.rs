@@ -0,0 +1,232 @@
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::One;
+use rand::{rngs::OsRng, RngCore};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use super::{g, hash_int, n, proof, session_key, Login, Message, Verifier};
+
+// Client logs in with simplified SRP, where the server picks u and B = g^b carries no trace of the
+// verifier.
+pub struct Client {
+  email: String,
+  password: Vec<u8>,
+  a: BigUint,
+  public: BigUint,
+}
+
+impl Client {
+  pub fn new(email: &str, password: &[u8]) -> Client {
+    let a = OsRng.gen_biguint_range(&BigUint::one(), &n());
+    let public = g().modpow(&a, &n());
+
+    Client {
+      email: email.to_string(),
+      password: Vec::from(password),
+      a,
+      public,
+    }
+  }
+}
+
+impl Login for Client {
+  fn hello(&self) -> Message {
+    Message::Hello {
+      email: self.email.clone(),
+      public: self.public.clone(),
+    }
+  }
+
+  // respond proves the key derived from S = B^(a + u * x).
+  fn respond(&mut self, challenge: Message) -> Result<Message, &'static str> {
+    let (salt, b, u) = match challenge {
+      Message::SimpleChallenge { salt, public, u } => (salt, public, u),
+      _ => return Err("expected a simple challenge"),
+    };
+
+    let x = hash_int(&[&salt, &self.password]);
+    let secret = b.modpow(&(&self.a + u * x), &n());
+
+    Ok(Message::Proof(proof(&session_key(&secret), &salt)))
+  }
+}
+
+// Session is a login in progress on the server.
+struct Session {
+  b: BigUint,
+  u: BigUint,
+  other: BigUint,
+}
+
+// Server checks simplified SRP logins for a single registered user.
+pub struct Server {
+  email: String,
+  salt: Vec<u8>,
+  v: BigUint,
+  session: Option<Session>,
+}
+
+impl Server {
+  pub fn new(email: &str, password: &[u8]) -> Server {
+    let mut salt = vec![0; 16];
+    OsRng.fill_bytes(&mut salt);
+    let x = hash_int(&[&salt, password]);
+
+    Server {
+      email: email.to_string(),
+      v: g().modpow(&x, &n()),
+      salt,
+      session: None,
+    }
+  }
+}
+
+impl Verifier for Server {
+  // challenge answers with the salt, B = g^b and a random 128 bit u.
+  fn challenge(&mut self, hello: Message) -> Result<Message, &'static str> {
+    let (email, other) = match hello {
+      Message::Hello { email, public } => (email, public),
+      _ => return Err("expected hello"),
+    };
+    if email != self.email {
+      return Err("unknown user");
+    }
+
+    let b = OsRng.gen_biguint_range(&BigUint::one(), &n());
+    let u = OsRng.gen_biguint(128);
+    let public = g().modpow(&b, &n());
+    self.session = Some(Session {
+      b,
+      u: u.clone(),
+      other,
+    });
+
+    Ok(Message::SimpleChallenge {
+      salt: self.salt.clone(),
+      public,
+      u,
+    })
+  }
+
+  // verify checks the proof against S = (A * v^u)^b.
+  fn verify(&mut self, proved: Message) -> Result<Message, &'static str> {
+    let mac = match proved {
+      Message::Proof(mac) => mac,
+      _ => return Err("expected a proof"),
+    };
+    let session = self.session.take().ok_or("no login in progress")?;
+
+    let n = n();
+    let secret = (session.other * self.v.modpow(&session.u, &n)).modpow(&session.b, &n);
+
+    Ok(Message::Verdict(
+      mac == proof(&session_key(&secret), &self.salt),
+    ))
+  }
+}
+
+// Capture is what an impostor server learns from one login: enough to test password guesses
+// offline.
+#[derive(Clone, Debug)]
+pub struct Capture {
+  pub email: String,
+  pub salt: Vec<u8>,
+  pub public: BigUint,
+  pub mac: Vec<u8>,
+}
+
+impl Capture {
+  // check reports whether password explains the captured proof. The impostor chose b = 1 and
+  // u = 1, so the client's secret was B^(a + x) = A * g^x.
+  pub fn check(&self, password: &[u8]) -> bool {
+    let n = n();
+    let x = hash_int(&[&self.salt, password]);
+    let secret = (&self.public * g().modpow(&x, &n)) % &n;
+
+    proof(&session_key(&secret), &self.salt) == self.mac
+  }
+}
+
+// Impostor stands in for the server, choosing its challenge so that the proof it gets back can be
+// checked against password guesses. It lets every login through.
+#[derive(Default)]
+pub struct Impostor {
+  hello: Option<(String, BigUint, Vec<u8>)>,
+  capture: Option<Capture>,
+}
+
+impl Impostor {
+  pub fn new() -> Impostor {
+    Impostor::default()
+  }
+
+  // capture is what the last completed login gave away.
+  pub fn capture(&self) -> Option<&Capture> {
+    self.capture.as_ref()
+  }
+}
+
+impl Verifier for Impostor {
+  // challenge sends B = g and u = 1, with a random salt of its own.
+  fn challenge(&mut self, hello: Message) -> Result<Message, &'static str> {
+    let mut salt = vec![0; 16];
+    OsRng.fill_bytes(&mut salt);
+
+    match hello {
+      Message::Hello { email, public } => self.hello = Some((email, public, salt.clone())),
+      _ => return Err("expected hello"),
+    }
+
+    Ok(Message::SimpleChallenge {
+      salt,
+      public: g(),
+      u: BigUint::one(),
+    })
+  }
+
+  fn verify(&mut self, proved: Message) -> Result<Message, &'static str> {
+    let mac = match proved {
+      Message::Proof(mac) => mac,
+      _ => return Err("expected a proof"),
+    };
+    let (email, public, salt) = self.hello.take().ok_or("no login in progress")?;
+
+    self.capture = Some(Capture {
+      email,
+      salt,
+      public,
+      mac,
+    });
+
+    Ok(Message::Verdict(true))
+  }
+}
+
+// crack tries every word against capture, split across threads, and returns the first that
+// checks out.
+pub fn crack<'w>(capture: &Capture, words: &[&'w str], threads: usize) -> Option<&'w str> {
+  let found = Mutex::new(None);
+  let done = AtomicBool::new(false);
+  let chunk = words.len().div_ceil(threads.max(1));
+
+  thread::scope(|s| {
+    for part in words.chunks(chunk.max(1)) {
+      let (found, done) = (&found, &done);
+      s.spawn(move || {
+        for w in part {
+          if done.load(Ordering::Relaxed) {
+            return;
+          }
+          if capture.check(w.as_bytes()) {
+            done.store(true, Ordering::Relaxed);
+            *found.lock().unwrap() = Some(*w);
+            return;
+          }
+        }
+      });
+    }
+  });
+
+  found.into_inner().unwrap()
+}
@@ -0,0 +1,191 @@
+use std::time::Instant;
+
+// ByteGuess is one recovered byte, with confidence from 0 (tied with the runner-up) to 1 (the
+// runner-up looked no different from the pack).
+#[derive(Debug)]
+pub struct ByteGuess {
+  pub byte: u8,
+  pub confidence: f64,
+}
+
+// Measurement is the outcome of timing every candidate for one position.
+struct Measurement {
+  byte: u8,
+  confidence: f64,
+  base: f64,
+  leader: f64,
+}
+
+pub struct Attacker<'a, F: Fn(&[u8]) -> bool> {
+  oracle: &'a F,
+  len: usize,
+  samples: usize,
+  max_resamples: usize,
+}
+
+impl<'a, F: Fn(&[u8]) -> bool> Attacker<'a, F> {
+  // new wraps an oracle that checks a mac of len bytes, comparing it a byte at a time and stopping
+  // at the first mismatch.
+  pub fn new(oracle: &F, len: usize) -> Attacker<'_, F> {
+    Attacker {
+      oracle,
+      len,
+      samples: 3,
+      max_resamples: 10,
+    }
+  }
+
+  // with_samples sets how many times each candidate is timed per round.
+  pub fn with_samples(self, samples: usize) -> Attacker<'a, F> {
+    Attacker { samples, ..self }
+  }
+
+  // with_max_resamples sets how many extra rounds an ambiguous position gets.
+  pub fn with_max_resamples(self, max_resamples: usize) -> Attacker<'a, F> {
+    Attacker {
+      max_resamples,
+      ..self
+    }
+  }
+
+  // recover finds the mac a byte at a time, taking the candidate that keeps the oracle busiest. If
+  // a position shows no slowdown over the last, the last byte was wrong, and is taken back.
+  pub fn recover(&self) -> Result<Vec<ByteGuess>, &'static str> {
+    let mut guesses = Vec::<ByteGuess>::new();
+    let mut measured = Vec::<(f64, f64)>::new();
+    let mut rejected = vec![Vec::<u8>::new(); self.len];
+    let mut backtracks = 0;
+
+    while guesses.len() < self.len {
+      let i = guesses.len();
+      let prefix: Vec<u8> = guesses.iter().map(|g| g.byte).collect();
+
+      let found = if i == self.len - 1 {
+        // The last byte has no later comparison to time, but the oracle accepts the right one.
+        let byte = (0..=255)
+          .filter(|b| !rejected[i].contains(b))
+          .find(|b| (self.oracle)(&self.mac(&prefix, *b)));
+        if let Some(byte) = byte {
+          guesses.push(ByteGuess {
+            byte,
+            confidence: 1.,
+          });
+        }
+
+        byte.is_some()
+      } else {
+        let m = self.measure(&prefix, &rejected[i]);
+
+        // With the prefix right, every candidate gets one comparison further than at the last
+        // position, so the pack should have slowed down by about what the leader stood out by.
+        // The leader is picked for standing out, so allow for it overstating the step.
+        let signal = match measured.last() {
+          Some((base, leader)) => m.base - base > (leader - base) / 3.,
+          None => true,
+        };
+        if signal {
+          guesses.push(ByteGuess {
+            byte: m.byte,
+            confidence: m.confidence,
+          });
+          measured.push((m.base, m.leader));
+        }
+
+        signal
+      };
+
+      if !found {
+        backtracks += 1;
+        if i == 0 || backtracks > 2 * self.len {
+          return Err("no signal left to follow");
+        }
+
+        let wrong = guesses.pop().unwrap();
+        measured.pop();
+        rejected[i].clear();
+        rejected[i - 1].push(wrong.byte);
+      }
+    }
+
+    Ok(guesses)
+  }
+
+  // measure times every candidate not excluded for the byte after prefix, sampling the leaders
+  // again while the best is hard to tell from the runner-up.
+  fn measure(&self, prefix: &[u8], excluded: &[u8]) -> Measurement {
+    let candidates: Vec<u8> = (0..=255).filter(|b| !excluded.contains(b)).collect();
+    let mut times = vec![Vec::<f64>::new(); 256];
+
+    // Time candidates in turn rather than back to back, so drift hits them all alike.
+    let sample = |candidates: &[u8], times: &mut Vec<Vec<f64>>| {
+      for _ in 0..self.samples {
+        for c in candidates {
+          times[*c as usize].push(self.time(&self.mac(prefix, *c)));
+        }
+      }
+    };
+    sample(&candidates, &mut times);
+
+    let mut round = 0;
+    loop {
+      let mut scores: Vec<(f64, u8)> = candidates
+        .iter()
+        .map(|c| (robust_mean(&times[*c as usize]), *c))
+        .collect();
+      scores.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+
+      let (leader, byte) = scores[0];
+      let second = scores.get(1).map(|s| s.0).unwrap_or(leader);
+      let base = scores[scores.len() / 2].0;
+
+      let confidence = if leader > base {
+        ((leader - second) / (leader - base)).min(1.)
+      } else {
+        0.
+      };
+
+      if confidence >= 0.5 || round >= self.max_resamples {
+        return Measurement {
+          byte,
+          confidence,
+          base,
+          leader,
+        };
+      }
+
+      let top: Vec<u8> = scores.iter().take(8).map(|s| s.1).collect();
+      sample(&top, &mut times);
+      round += 1;
+    }
+  }
+
+  fn mac(&self, prefix: &[u8], b: u8) -> Vec<u8> {
+    let mut mac = Vec::from(prefix);
+    mac.push(b);
+    mac.resize(self.len, 0);
+
+    mac
+  }
+
+  fn time(&self, mac: &[u8]) -> f64 {
+    let start = Instant::now();
+    (self.oracle)(mac);
+
+    start.elapsed().as_secs_f64()
+  }
+}
+
+// robust_mean is the median of a few samples, and the mean of the middle half of more, either way
+// ignoring the occasional stall.
+fn robust_mean(xs: &[f64]) -> f64 {
+  let mut xs = Vec::from(xs);
+  xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+  let n = xs.len();
+  if n < 4 {
+    return xs[n / 2];
+  }
+
+  let middle = &xs[n / 4..n - n / 4];
+  middle.iter().sum::<f64>() / middle.len() as f64
+}
@@ -55,7 +55,7 @@ mod tests {
       String::from("Burning 'em, if you ain't quick and nimble\nI go crazy when I hear a cymbal");
     let key = String::from("ICE").as_bytes().to_vec();
 
-    let got = to_hex(&xor(&line.as_bytes().to_vec(), &key));
+    let got = to_hex(&xor(line.as_bytes(), &key));
 
     assert_eq!(
       got,
@@ -75,8 +75,8 @@ mod tests {
     );
     assert_eq!(
       hamming_distance(
-        &String::from("this is a test").as_bytes(),
-        &String::from("wokka wokka!!!").as_bytes()
+        String::from("this is a test").as_bytes(),
+        String::from("wokka wokka!!!").as_bytes()
       ),
       37
     );
@@ -117,7 +117,7 @@ mod tests {
     let mut found = "";
     for s in file.split_ascii_whitespace() {
       if let Mode::CBC = detect_block_mode(&from_hex(s).unwrap()) {
-        if found != "" {
+        if !found.is_empty() {
           panic!("found multiple ECB candidates");
         }
         found = s;
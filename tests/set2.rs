@@ -11,7 +11,10 @@ mod tests {
 
   #[test]
   fn challenge09() {
-    assert_eq!(aes::pad(b"YELLOW SUBMARINE", 16), b"YELLOW SUBMARINE");
+    assert_eq!(
+      aes::pad(b"YELLOW SUBMARINE", 16),
+      b"YELLOW SUBMARINE\x10\x10\x10\x10\x10\x10\x10\x10\x10\x10\x10\x10\x10\x10\x10\x10"
+    );
     assert_eq!(aes::pad(b"YELLOW SUBMARINE", 17), b"YELLOW SUBMARINE\x01");
     assert_eq!(
       aes::pad(b"YELLOW SUBMARINE", 18),
@@ -147,6 +150,9 @@ mod tests {
   fn challenge15() {
     assert!(aes::unpad(b"YELLOW SUBMARINE\x03".to_vec()).is_err());
     assert!(aes::unpad(b"YELLOW SUBMARINE\x03\x03".to_vec()).is_err());
+    assert!(aes::unpad(b"YELLOW SUBMARINE\x00".to_vec()).is_err());
+    assert!(aes::unpad(b"YELLOW SUBMARINE\x11".to_vec()).is_err());
+    assert!(aes::unpad(Vec::new()).is_err());
     assert_eq!(
       aes::unpad(b"YELLOW SUBMARINE\x03\x03\x03".to_vec()).unwrap(),
       b"YELLOW SUBMARINE"
@@ -173,7 +179,7 @@ mod tests {
 
   #[test]
   fn challenge16() {
-    let cbc = RandomKeyCoder::new(&|k: &[u8]| -> CBC { aes::CBC::new(k, &vec![0; 16]).unwrap() })
+    let cbc = RandomKeyCoder::new(&|k: &[u8]| -> CBC { aes::CBC::new(k, &[0; 16]).unwrap() })
       .with_prefix(b"comment1=cooking%20MCs;userdata=".to_vec())
       .with_suffix(b";comment2=%20like%20a%20pound%20of%20bacon".to_vec());
 
@@ -206,7 +212,7 @@ mod tests {
       false
     };
 
-    assert!(!is_admin(&enc.encrypt(&";admin=true".as_bytes())));
+    assert!(!is_admin(&enc.encrypt(";admin=true".as_bytes())));
 
     let inj = cbc::Injector::new(&enc);
     let ct = inj.inject(2, ";admin=true;abc=", ";comment2=%20lik");
@@ -0,0 +1,278 @@
+#[cfg(test)]
+mod tests {
+  use rand::{rngs::OsRng, RngCore, SeedableRng};
+  use std::cell::Cell;
+  use std::fs;
+
+  use matasano::aes::{Decrypter, Encrypter, CBC, CTR, ECB};
+  use matasano::ctr;
+  use matasano::mt19937::{self, Mt19937, Mt19937_64};
+  use matasano::mt_seed::{self, Clock};
+  use matasano::padding_oracle;
+  use matasano::*;
+
+  #[test]
+  fn challenge17() {
+    let strings = [
+      "MDAwMDAwTm93IHRoYXQgdGhlIHBhcnR5IGlzIGp1bXBpbmc=",
+      "MDAwMDAxV2l0aCB0aGUgYmFzcyBraWNrZWQgaW4gYW5kIHRoZSBWZWdhJ3MgYXJlIHB1bXBpbic=",
+      "MDAwMDAyUXVpY2sgdG8gdGhlIHBvaW50LCB0byB0aGUgcG9pbnQsIG5vIGZha2luZw==",
+      "MDAwMDAzQ29va2luZyBNQydzIGxpa2UgYSBwb3VuZCBvZiBiYWNvbg==",
+      "MDAwMDA0QnVybmluZyAnZW0sIGlmIHlvdSBhaW4ndCBxdWljayBhbmQgbmltYmxl",
+      "MDAwMDA1SSBnbyBjcmF6eSB3aGVuIEkgaGVhciBhIGN5bWJhbA==",
+      "MDAwMDA2QW5kIGEgaGlnaCBoYXQgd2l0aCBhIHNvdXBlZCB1cCB0ZW1wbw==",
+      "MDAwMDA3SSdtIG9uIGEgcm9sbCwgaXQncyB0aW1lIHRvIGdvIHNvbG8=",
+      "MDAwMDA4b2xsaW4nIGluIG15IGZpdmUgcG9pbnQgb2g=",
+      "MDAwMDA5aXRoIG15IHJhZy10b3AgZG93biBzbyBteSBoYWlyIGNhbiBibG93",
+    ];
+
+    let mut iv = [0u8; 16];
+    OsRng.fill_bytes(&mut iv);
+
+    let cbc = RandomKeyCoder::new(&|k: &[u8]| -> CBC { CBC::new(k, &iv).unwrap() });
+
+    // The coder's own iv only garbles the sacrificial first block, which unpadding never looks at.
+    let oracle = |iv: &[u8], ct: &[u8]| -> bool {
+      let mut bb = Vec::from(iv);
+      bb.extend(ct);
+
+      cbc.decrypt(&bb).is_ok()
+    };
+
+    let decrypter = padding_oracle::Decrypter::new(&oracle);
+
+    for s in strings.iter() {
+      let plain = from_base64(s).unwrap();
+      let ct = cbc.encrypt(&plain);
+
+      assert_eq!(decrypter.decrypt(&iv, &ct).unwrap(), plain);
+    }
+
+    // Block aligned input gets a whole 16 byte block of padding, so a trailing 0x10 survives the
+    // round trip.
+    let aligned = b"AAAAAAAAAAAAAAA\x10";
+    let ecb = ECB::new(b"YELLOW SUBMARINE").unwrap();
+    assert_eq!(ecb.decrypt(&ecb.encrypt(aligned)).unwrap(), aligned);
+    let ct = cbc.encrypt(aligned);
+    assert_eq!(ct.len(), 32);
+    assert_eq!(cbc.decrypt(&ct).unwrap(), aligned);
+    assert_eq!(decrypter.decrypt(&iv, &ct).unwrap(), aligned);
+  }
+
+  #[test]
+  fn challenge18() {
+    let ct =
+      from_base64("L77na/nrFsKvynd6HzOoG7GHTLXsTVu9qvY/2syLXzhPweyyMTJULu/6/kXX0KSvoOLSFQ==")
+        .unwrap();
+
+    let coder = CTR::new(b"YELLOW SUBMARINE", 0).unwrap();
+    let dec = coder.decrypt(&ct).unwrap();
+
+    assert_eq!(
+      String::from_utf8(dec.clone()).unwrap(),
+      "Yo, VIP Let's kick it Ice, Ice, baby Ice, Ice, baby "
+    );
+    assert_eq!(coder.encrypt(&dec), ct);
+
+    let ctr = RandomKeyCoder::new(&|k: &[u8]| -> CTR { CTR::new(k, 0).unwrap() });
+    let enc = ctr.encrypt(b"no padding, any length");
+    assert_eq!(enc.len(), 22);
+    assert_eq!(ctr.decrypt(&enc).unwrap(), b"no padding, any length");
+  }
+
+  // lyrics returns the non-empty lines of the challenge 7 plaintext.
+  fn lyrics() -> Vec<Vec<u8>> {
+    let file = fs::read_to_string("tests/data/7.txt").expect("failed to read file");
+    let contents = file.replace("\n", "");
+
+    let bb = from_base64(&contents).expect("failed to decode64 contents");
+    let dec = ECB::new(b"YELLOW SUBMARINE").unwrap().decrypt(&bb).unwrap();
+
+    dec
+      .split(|b| *b == b'\n')
+      .filter(|l| !l.is_empty())
+      .map(Vec::from)
+      .collect()
+  }
+
+  #[test]
+  fn challenge19() {
+    let lines = lyrics();
+    let ctr = RandomKeyCoder::new(&|k: &[u8]| -> CTR { CTR::new(k, 0).unwrap() });
+    let cts: Vec<Vec<u8>> = lines.iter().map(|l| ctr.encrypt(l)).collect();
+
+    let mut decrypter = ctr::FixedNonceDecrypter::new(&cts);
+
+    // Guessing the rest of the longest line fixes every line as far as it goes.
+    let (longest, _) = lines
+      .iter()
+      .enumerate()
+      .max_by_key(|(_, l)| l.len())
+      .unwrap();
+    decrypter.pin(longest, 0, &lines[longest]).unwrap();
+    assert_eq!(decrypter.keystream().len(), lines[longest].len());
+
+    for (got, want) in decrypter.plaintexts().iter().zip(&lines) {
+      assert_eq!(got, want);
+    }
+
+    assert!(decrypter.pin(longest, 100, b"x").is_err());
+    assert!(decrypter.pin(lines.len(), 0, b"x").is_err());
+  }
+
+  #[test]
+  fn challenge20() {
+    let lines = lyrics();
+    let ctr = RandomKeyCoder::new(&|k: &[u8]| -> CTR { CTR::new(k, 0).unwrap() });
+    let cts: Vec<Vec<u8>> = lines.iter().map(|l| ctr.encrypt(l)).collect();
+
+    let mut decrypter = ctr::FixedNonceDecrypter::new(&cts);
+    let l = decrypter.keystream().len();
+    assert_eq!(l, lines.iter().map(|l| l.len()).min().unwrap());
+
+    let mut correct = 0;
+    for (got, want) in decrypter.plaintexts().iter().zip(&lines) {
+      correct += got.iter().zip(want).filter(|(a, b)| a == b).count();
+    }
+
+    // Statistics can't tell the case of the first letter, but should get the rest.
+    assert!(correct * 10 > lines.len() * l * 9);
+
+    decrypter.pin(0, 0, &lines[0][..1]).unwrap();
+    for (got, want) in decrypter.plaintexts().iter().zip(&lines) {
+      assert_eq!(got[..], want[..l]);
+    }
+  }
+
+  #[test]
+  fn challenge21() {
+    // Reference outputs of mt19937ar.c and mt19937-64.c.
+    let mut mt = Mt19937::from_array(&[0x123, 0x234, 0x345, 0x456]);
+    let got: Vec<u32> = (0..5).map(|_| mt.next_u32()).collect();
+    assert_eq!(
+      got,
+      [1067595299, 955945823, 477289528, 4107218783, 4228976476]
+    );
+
+    let mut mt = Mt19937_64::from_array(&[0x12345, 0x23456, 0x34567, 0x45678]);
+    let got: Vec<u64> = (0..3).map(|_| mt.next_u64()).collect();
+    assert_eq!(
+      got,
+      [
+        7266447313870364031,
+        4946485549665804864,
+        16945909448695747420
+      ]
+    );
+
+    // The 10000th output for the default seed, as required of C++'s std::mt19937{,_64}.
+    let mut mt = Mt19937::new(5489);
+    assert_eq!(mt.next_u32(), 3499211612);
+    assert_eq!((1..10000).map(|_| mt.next_u32()).last(), Some(4123659995));
+
+    let mut mt = Mt19937_64::seed_from_u64(5489);
+    assert_eq!(
+      (0..10000).map(|_| mt.next_u64()).last(),
+      Some(9981545732273789042)
+    );
+
+    // Seeded generators make the random coders reproducible.
+    let a = RandomKeyCoder::from_rng(&mut Mt19937::new(1), &|k: &[u8]| -> ECB {
+      ECB::new(k).unwrap()
+    });
+    let b = RandomKeyCoder::from_rng(&mut Mt19937::new(1), &|k: &[u8]| -> ECB {
+      ECB::new(k).unwrap()
+    });
+    assert_eq!(
+      a.encrypt(b"YELLOW SUBMARINE"),
+      b.encrypt(b"YELLOW SUBMARINE")
+    );
+
+    let a = RandomEncrypter::from_rng(&mut Mt19937_64::new(1), b"YELLOW SUBMARINE").unwrap();
+    let b = RandomEncrypter::from_rng(&mut Mt19937_64::new(1), b"YELLOW SUBMARINE").unwrap();
+    assert_eq!(a.ciphertext, b.ciphertext);
+  }
+
+  struct FakeClock {
+    now: Cell<u32>,
+  }
+
+  impl FakeClock {
+    fn wait(&self, secs: u32) {
+      self.now.set(self.now.get() + secs);
+    }
+  }
+
+  impl Clock for FakeClock {
+    fn now(&self) -> u32 {
+      self.now.get()
+    }
+  }
+
+  #[test]
+  fn challenge22() {
+    let clock = FakeClock {
+      now: Cell::new(1_600_000_000),
+    };
+
+    clock.wait(500);
+    let seed = clock.now();
+    let first = Mt19937::new(seed).next_u32();
+    clock.wait(45);
+
+    assert_eq!(mt_seed::crack_time_seed(first, &clock, 2000), Some(seed));
+    assert_eq!(mt_seed::crack_time_seed(first, &clock, 30), None);
+  }
+
+  #[test]
+  fn challenge23() {
+    for _ in 0..1000 {
+      let y = OsRng.next_u32();
+      assert_eq!(mt19937::untemper(mt19937::temper(y)), y);
+    }
+
+    let mut mt = Mt19937::new(OsRng.next_u32());
+    let outputs: Vec<u32> = (0..624).map(|_| mt.next_u32()).collect();
+
+    let mut clone = mt19937::clone_from_outputs(&outputs);
+    for _ in 0..1000 {
+      assert_eq!(clone.next_u32(), mt.next_u32());
+    }
+
+    // Outputs beyond the first 624 are skipped over.
+    let mut mt = Mt19937::new(OsRng.next_u32());
+    let outputs: Vec<u32> = (0..1000).map(|_| mt.next_u32()).collect();
+
+    let mut clone = mt19937::clone_from_outputs(&outputs);
+    for _ in 0..1000 {
+      assert_eq!(clone.next_u32(), mt.next_u32());
+    }
+  }
+
+  #[test]
+  fn challenge24() {
+    let seed = 0xbeef;
+    let cipher = mt19937::StreamCipher::new(seed);
+
+    let mut plain = b"\x9c\x04\xe1\x7a\x52\xd8\x33".to_vec();
+    plain.extend(b"AAAAAAAAAAAAAA");
+
+    let ct = cipher.encrypt(&plain);
+    assert_eq!(cipher.decrypt(&ct).unwrap(), plain);
+    assert_eq!(
+      mt_seed::crack_stream_seed(&ct, b"AAAAAAAAAAAAAA"),
+      Some(seed)
+    );
+
+    let clock = FakeClock {
+      now: Cell::new(1_600_000_000),
+    };
+
+    let token = mt_seed::reset_token(&clock);
+    clock.wait(45);
+    assert!(mt_seed::is_time_seeded_token(&token, &clock, 600));
+
+    let random = b"\x5b\xe2\x90\x0d\x47\xc1\x8a\x6f\x13\xfe\x22\xb4\x71\x08\xd9\x3c";
+    assert!(!mt_seed::is_time_seeded_token(random, &clock, 600));
+  }
+}
@@ -0,0 +1,333 @@
+#[cfg(test)]
+mod tests {
+  use rand::{rngs::OsRng, RngCore};
+  use std::cell::Cell;
+  use std::fs;
+  use std::thread;
+  use std::time::{Duration, Instant};
+
+  use matasano::aes::{Decrypter, Encrypter, CBC, CTR, ECB};
+  use matasano::cbc;
+  use matasano::ctr;
+  use matasano::hash::md4::{self, Md4};
+  use matasano::hash::sha1::{self, Sha1};
+  use matasano::hmac_server;
+  use matasano::timing;
+  use matasano::*;
+
+  #[test]
+  fn challenge25() {
+    let file = fs::read_to_string("tests/data/7.txt").expect("failed to read file");
+    let contents = file.replace("\n", "");
+
+    let bb = from_base64(&contents).expect("failed to decode64 contents");
+    let plain = ECB::new(b"YELLOW SUBMARINE").unwrap().decrypt(&bb).unwrap();
+
+    let mut key = [0u8; 16];
+    OsRng.fill_bytes(&mut key);
+    let coder = CTR::new(&key, OsRng.next_u64()).unwrap();
+
+    let ct = coder.encrypt(&plain);
+
+    let mut edited = ct.clone();
+    coder.edit(&mut edited, 1000, b"EDITED").unwrap();
+    let dec = coder.decrypt(&edited).unwrap();
+    assert_eq!(&dec[1000..1006], b"EDITED");
+    assert_eq!(dec[..1000], plain[..1000]);
+    assert_eq!(dec[1006..], plain[1006..]);
+
+    let l = edited.len();
+    coder.edit(&mut edited, l - 2, b"tail").unwrap();
+    assert!(coder.decrypt(&edited).unwrap().ends_with(b"tail"));
+    assert!(coder.edit(&mut edited, ct.len() + 10, b"x").is_err());
+
+    let edit = |ct: &[u8], offset: usize, newtext: &[u8]| -> Vec<u8> {
+      let mut bb = Vec::from(ct);
+      coder.edit(&mut bb, offset, newtext).unwrap();
+
+      bb
+    };
+
+    let decrypter = ctr::EditDecrypter::new(&edit);
+    assert_eq!(decrypter.decrypt(&ct), plain);
+  }
+
+  struct TransformingEncrypter<'a, T> {
+    encrypter: &'a T,
+    transformer: &'a dyn Fn(&[u8]) -> Vec<u8>,
+  }
+
+  impl<'a, T: aes::Encrypter> aes::Encrypter for TransformingEncrypter<'a, T> {
+    fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+      let transformer = self.transformer;
+
+      self.encrypter.encrypt(&transformer(plaintext))
+    }
+  }
+
+  #[test]
+  fn challenge26() {
+    let ctr = RandomKeyCoder::new(&|k: &[u8]| -> CTR { CTR::new(k, OsRng.next_u64()).unwrap() })
+      .with_prefix(b"comment1=cooking%20MCs;userdata=".to_vec())
+      .with_suffix(b";comment2=%20like%20a%20pound%20of%20bacon".to_vec());
+
+    let enc = TransformingEncrypter {
+      encrypter: &ctr,
+      transformer: &|s: &[u8]| -> Vec<u8> {
+        let mut bb = Vec::<u8>::new();
+
+        for v in s {
+          match v {
+            b';' => bb.extend(b"%3B"),
+            b'=' => bb.extend(b"%3D"),
+            _ => bb.push(*v),
+          }
+        }
+
+        bb
+      },
+    };
+
+    let is_admin = |ct: &[u8]| -> bool {
+      let dec = ctr.decrypt(ct).unwrap();
+
+      for kv in String::from_utf8_lossy(&dec).split(";") {
+        if kv == "admin=true" {
+          return true;
+        }
+      }
+
+      false
+    };
+
+    assert!(!is_admin(&enc.encrypt(";admin=true".as_bytes())));
+
+    assert_eq!(prefix_len(&enc, 1), 32);
+
+    let inj = ctr::Injector::new(&enc);
+    let ct = inj.inject(";admin=true;");
+
+    assert!(is_admin(&ct));
+  }
+
+  struct AsciiChecker<'a, T> {
+    decrypter: &'a T,
+  }
+
+  impl<'a, T: aes::Decrypter> aes::Decrypter for AsciiChecker<'a, T> {
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, aes::Error> {
+      let plain = self.decrypter.decrypt(ciphertext)?;
+      if plain.iter().any(|b| *b > 127) {
+        return Err(aes::Error::Rejected(plain));
+      }
+
+      Ok(plain)
+    }
+  }
+
+  #[test]
+  fn challenge27() {
+    let mut key = [0u8; 16];
+    OsRng.fill_bytes(&mut key);
+    let cbc = CBC::new(&key, &key).unwrap();
+
+    let checker = AsciiChecker { decrypter: &cbc };
+
+    let ct =
+      cbc.encrypt(b"comment1=cooking%20MCs;userdata=;comment2=%20like%20a%20pound%20of%20bacon");
+    assert!(checker.decrypt(&ct).is_ok());
+
+    let recoverer = cbc::KeyRecoverer::new(&checker);
+    assert_eq!(recoverer.recover(&ct).unwrap(), key);
+    assert!(recoverer.recover(&ct[..32]).is_err());
+  }
+
+  #[test]
+  fn challenge28() {
+    assert_eq!(
+      to_hex(&Sha1::digest(b"")),
+      "da39a3ee5e6b4b0d3255bfef95601890afd80709"
+    );
+    assert_eq!(
+      to_hex(&Sha1::digest(b"abc")),
+      "a9993e364706816aba3e25717850c26c9cd0d89d"
+    );
+    assert_eq!(
+      to_hex(&Sha1::digest(
+        b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+      )),
+      "84983e441c3bd26ebaae4aa1f95129e5e54670f1"
+    );
+
+    let mut s = Sha1::new();
+    for _ in 0..1000 {
+      s.update(&[b'a'; 1000]);
+    }
+    assert_eq!(
+      to_hex(&s.finalize()),
+      "34aa973cd4c4daa4f61eeb2bdbad27316534016f"
+    );
+
+    let mut s = Sha1::new();
+    s.update(&[b'a'; 150]);
+    let (h, len) = s.state();
+    assert_eq!(len, 128);
+    let mut resumed = Sha1::from_state(h, len);
+    resumed.update(&[b'a'; 22]);
+    assert_eq!(resumed.finalize(), s.finalize());
+
+    let mut key = vec![0; 16];
+    OsRng.fill_bytes(&mut key);
+
+    let msg = b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
+    let mac = sha1::mac(&key, msg);
+    assert_eq!(mac, sha1::mac(&key, msg));
+    assert_ne!(
+      mac,
+      sha1::mac(&key, b"comment1=cooking%20MCs;userdata=foo;admin=true")
+    );
+    assert_ne!(mac, sha1::mac(b"YELLOW SUBMARINE", msg));
+  }
+
+  #[test]
+  fn challenge29() {
+    let mut key = vec![0; (OsRng.next_u32() % 32 + 1) as usize];
+    OsRng.fill_bytes(&mut key);
+
+    let verify = |msg: &[u8], mac: &[u8; 20]| -> bool { sha1::mac(&key, msg) == *mac };
+
+    let msg = b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
+    let mac = sha1::mac(&key, msg);
+
+    let forged = (0..64)
+      .map(|l| sha1::length_extend(&mac, msg, l, b";admin=true"))
+      .find(|(m, mac)| verify(m, mac));
+
+    let (m, _) = forged.expect("no key length gave a valid mac");
+    assert!(m.starts_with(msg));
+    assert!(m.ends_with(b";admin=true"));
+  }
+
+  #[test]
+  fn challenge30() {
+    assert_eq!(
+      to_hex(&Md4::digest(b"")),
+      "31d6cfe0d16ae931b73c59d7e0c089c0"
+    );
+    assert_eq!(
+      to_hex(&Md4::digest(b"a")),
+      "bde52cb31de33e46245e05fbdbd6fb24"
+    );
+    assert_eq!(
+      to_hex(&Md4::digest(b"abc")),
+      "a448017aaf21d8525fc10ae87aa6729d"
+    );
+    assert_eq!(
+      to_hex(&Md4::digest(b"message digest")),
+      "d9130a8164549fe818874806e1c7014b"
+    );
+    assert_eq!(
+      to_hex(&Md4::digest(
+        b"12345678901234567890123456789012345678901234567890123456789012345678901234567890"
+      )),
+      "e33b4ddc9c38f2199c3e7b164fcc0536"
+    );
+
+    let mut s = Md4::new();
+    s.update(&[b'a'; 150]);
+    let (h, len) = s.state();
+    assert_eq!(len, 128);
+    let mut resumed = Md4::from_state(h, len);
+    resumed.update(&[b'a'; 22]);
+    assert_eq!(resumed.finalize(), s.finalize());
+
+    let mut key = vec![0; (OsRng.next_u32() % 32 + 1) as usize];
+    OsRng.fill_bytes(&mut key);
+
+    let verify = |msg: &[u8], mac: &[u8; 16]| -> bool { md4::mac(&key, msg) == *mac };
+
+    let msg = b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
+    let mac = md4::mac(&key, msg);
+
+    let forged = (0..64)
+      .map(|l| md4::length_extend(&mac, msg, l, b";admin=true"))
+      .find(|(m, mac)| verify(m, mac));
+
+    let (m, _) = forged.expect("no key length gave a valid mac");
+    assert!(m.starts_with(msg));
+    assert!(m.ends_with(b";admin=true"));
+  }
+
+  #[test]
+  fn challenge31() {
+    assert_eq!(
+      to_hex(&sha1::hmac(
+        b"key",
+        b"The quick brown fox jumps over the lazy dog"
+      )),
+      "de7c9b85b8b78aa6bc8a7a36f70a90701c9db4d9"
+    );
+
+    let mut key = vec![0; 16];
+    OsRng.fill_bytes(&mut key);
+
+    let delay = Duration::from_millis(2);
+    let server = hmac_server::Server::start(&key, delay).unwrap();
+    let mac = sha1::hmac(&key, b"foo");
+
+    assert!(hmac_server::request(server.addr(), "foo", &mac).unwrap());
+    assert!(!hmac_server::request(server.addr(), "bar", &mac).unwrap());
+    assert!(!hmac_server::request(server.addr(), "foo", &mac[..19]).unwrap());
+
+    // Every right byte costs another delay, and the first wrong one ends the comparison.
+    let start = Instant::now();
+    hmac_server::request(server.addr(), "foo", &mac).unwrap();
+    assert!(start.elapsed() >= delay * 20);
+
+    let mut wrong = mac;
+    wrong[0] ^= 1;
+    let start = Instant::now();
+    hmac_server::request(server.addr(), "foo", &wrong).unwrap();
+    assert!(start.elapsed() < delay * 20);
+  }
+
+  #[test]
+  fn challenge32() {
+    let mut key = vec![0; 16];
+    OsRng.fill_bytes(&mut key);
+    let mac = sha1::hmac(&key, b"foo");
+
+    // Only part of the mac is checked, to keep the run short, and each check stalls for up to as
+    // long again as the per-byte leak.
+    let delay = Duration::from_micros(500);
+    let checked = &mac[..4];
+    let oracle = |sig: &[u8]| -> bool {
+      thread::sleep(Duration::from_micros(OsRng.next_u64() % 500));
+      hmac_server::insecure_compare(checked, sig, delay)
+    };
+
+    let guesses = timing::Attacker::new(&oracle, 4).recover().unwrap();
+    let got: Vec<u8> = guesses.iter().map(|g| g.byte).collect();
+    assert_eq!(got, checked);
+    assert!(guesses.iter().all(|g| g.confidence > 0.));
+
+    // A decoy that looks right while the first byte is measured leaves no signal at the second,
+    // and is taken back.
+    let decoy = checked[0] ^ 0xff;
+    let calls = Cell::new(0);
+    let oracle = |sig: &[u8]| -> bool {
+      calls.set(calls.get() + 1);
+      if sig[0] == decoy && calls.get() <= 3 * 256 {
+        thread::sleep(delay * 4);
+      }
+      hmac_server::insecure_compare(checked, sig, delay)
+    };
+
+    let guesses = timing::Attacker::new(&oracle, 4)
+      .with_samples(3)
+      .recover()
+      .unwrap();
+    let got: Vec<u8> = guesses.iter().map(|g| g.byte).collect();
+    assert_eq!(got, checked);
+  }
+}
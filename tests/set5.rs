@@ -0,0 +1,217 @@
+#[cfg(test)]
+mod tests {
+  use num_bigint::BigUint;
+  use std::net::{TcpListener, TcpStream};
+  use std::thread;
+
+  use matasano::actor::{self, Side};
+  use matasano::dh::{self, DH};
+  use matasano::dh_echo::{self, GInjector, MaliciousG, Message};
+  use matasano::hash::sha256::{self, Sha256};
+  use matasano::srp::{self, simple};
+  use matasano::to_hex;
+
+  #[test]
+  fn challenge33() {
+    let p = BigUint::from(37u32);
+    let g = BigUint::from(5u32);
+
+    let a = DH::new(&p, &g);
+    let b = DH::new(&p, &g);
+    assert!(a.public < p);
+    assert_eq!(a.shared_secret(&b.public), b.shared_secret(&a.public));
+
+    let a = DH::nist();
+    let b = DH::nist();
+    assert_eq!(a.shared_secret(&b.public), b.shared_secret(&a.public));
+    assert_ne!(a.public, b.public);
+
+    let key = a.key(&b.public);
+    assert_eq!(key.len(), 16);
+    assert_eq!(key, b.key(&a.public));
+    assert_eq!(key, dh::aes_key(&a.shared_secret(&b.public)));
+  }
+
+  #[test]
+  fn challenge34() {
+    let text = b"Ice Ice Baby, too cold, too cold";
+
+    assert_eq!(dh_echo::echo(text, None).unwrap(), text);
+
+    let mut mitm = dh_echo::KeyReplacer::new();
+    assert_eq!(dh_echo::echo(text, Some(&mut mitm)).unwrap(), text);
+    assert_eq!(mitm.messages(), &[text.to_vec(), text.to_vec()]);
+
+    // A scripted attacker sees every message, and whatever it tampers with shows up at the far end.
+    let mut seen = Vec::<Side>::new();
+    let mut tamper = |from: Side, message: Message| {
+      seen.push(from);
+      match message {
+        Message::Data(mut sealed) if from == Side::B => {
+          sealed[0] ^= 1;
+          Message::Data(sealed)
+        }
+        m => m,
+      }
+    };
+    let echoed = dh_echo::echo(text, Some(&mut tamper));
+    assert_ne!(echoed, Ok(text.to_vec()));
+    assert_eq!(seen, vec![Side::A, Side::B, Side::A, Side::B]);
+  }
+
+  #[test]
+  fn challenge35() {
+    let text = b"Ice Ice Baby, too cold, too cold";
+
+    assert_eq!(dh_echo::negotiated_echo(text, false, None).unwrap(), text);
+    assert_eq!(dh_echo::negotiated_echo(text, true, None).unwrap(), text);
+
+    for g in &[MaliciousG::One, MaliciousG::P, MaliciousG::PMinusOne] {
+      let mut mitm = GInjector::new(*g);
+      assert_eq!(
+        dh_echo::negotiated_echo(text, false, Some(&mut mitm)).unwrap(),
+        text
+      );
+      assert_eq!(mitm.messages(), &[text.to_vec(), text.to_vec()]);
+
+      let mut mitm = GInjector::new(*g);
+      assert_eq!(
+        dh_echo::negotiated_echo(text, true, Some(&mut mitm)),
+        Err("unsafe generator")
+      );
+      assert!(mitm.messages().is_empty());
+    }
+  }
+
+  #[test]
+  fn challenge36() {
+    assert_eq!(
+      to_hex(&Sha256::digest(b"")),
+      "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+      to_hex(&Sha256::digest(&[b'a'; 1000])),
+      "41edece42d63e8d9bf515a9ba6932e1c20cbc9f5a5d134645adb5db1b9737ea3"
+    );
+    assert_eq!(
+      to_hex(&sha256::hmac(b"Jefe", b"what do ya want for nothing?")),
+      "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+
+    let email = "alice@example.com";
+    let password = b"hunter2";
+
+    // Over a channel.
+    let (mut c, mut s) = actor::pair();
+    let mut server = srp::Server::new(email, password);
+    let mut client = srp::Client::new(email, password);
+    thread::scope(|scope| {
+      let served = scope.spawn(|| srp::serve(&mut server, &mut s));
+      assert_eq!(srp::login(&mut client, &mut c), Ok(true));
+      assert_eq!(served.join().unwrap(), Ok(true));
+    });
+    assert!(client.key().is_some());
+    assert_eq!(client.key(), server.key());
+
+    // Over TCP, with the wrong password.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut server = srp::Server::new(email, password);
+    thread::scope(|scope| {
+      let served = scope.spawn(|| {
+        let (stream, _) = listener.accept().unwrap();
+        srp::serve(&mut server, &mut srp::Stream::new(stream))
+      });
+
+      let mut link = srp::Stream::new(TcpStream::connect(addr).unwrap());
+      let mut client = srp::Client::new(email, b"hunter3");
+      assert_eq!(srp::login(&mut client, &mut link), Ok(false));
+      assert_eq!(served.join().unwrap(), Ok(false));
+    });
+    assert!(server.key().is_none());
+
+    let mut client = srp::Client::new(email, password);
+    let mut link = srp::Stream::new(TcpStream::connect(addr).unwrap());
+    let mut server = srp::Server::new(email, password);
+    thread::scope(|scope| {
+      let served = scope.spawn(|| {
+        let (stream, _) = listener.accept().unwrap();
+        srp::serve(&mut server, &mut srp::Stream::new(stream))
+      });
+      assert_eq!(srp::login(&mut client, &mut link), Ok(true));
+      assert_eq!(served.join().unwrap(), Ok(true));
+    });
+    assert_eq!(client.key(), server.key());
+  }
+
+  // exchange runs one login of client against server over a channel, returning both sides' verdicts.
+  fn exchange<S: srp::Verifier + Send>(
+    client: &mut dyn srp::Login,
+    server: &mut S,
+  ) -> (Result<bool, &'static str>, Result<bool, &'static str>) {
+    let (mut c, mut s) = actor::pair();
+    thread::scope(|scope| {
+      let served = scope.spawn(move || srp::serve(server, &mut s));
+      let logged_in = srp::login(client, &mut c);
+
+      (logged_in, served.join().unwrap())
+    })
+  }
+
+  #[test]
+  fn challenge37() {
+    let email = "alice@example.com";
+    let mut server = srp::Server::new(email, b"correct horse battery staple");
+
+    for multiple in 0..3 {
+      let mut client = srp::ZeroKeyClient::new(email, multiple);
+      assert_eq!(exchange(&mut client, &mut server), (Ok(true), Ok(true)));
+    }
+
+    let mut client = srp::Client::new(email, b"Tr0ub4dor&3");
+    assert_eq!(exchange(&mut client, &mut server), (Ok(false), Ok(false)));
+  }
+
+  #[test]
+  fn challenge38() {
+    let email = "alice@example.com";
+    let words: Vec<String> = (0..200).map(|i| format!("word{}", i)).collect();
+    let words: Vec<&str> = words.iter().map(|w| w.as_str()).collect();
+
+    let mut server = simple::Server::new(email, b"word123");
+    let mut client = simple::Client::new(email, b"word123");
+    assert_eq!(exchange(&mut client, &mut server), (Ok(true), Ok(true)));
+    let mut client = simple::Client::new(email, b"word321");
+    assert_eq!(exchange(&mut client, &mut server), (Ok(false), Ok(false)));
+
+    let mut impostor = simple::Impostor::new();
+    let mut client = simple::Client::new(email, b"word123");
+    assert_eq!(exchange(&mut client, &mut impostor), (Ok(true), Ok(true)));
+
+    let capture = impostor.capture().unwrap();
+    assert_eq!(capture.email, email);
+    assert_eq!(simple::crack(capture, &words, 4), Some("word123"));
+    assert_eq!(simple::crack(capture, &words, 1), Some("word123"));
+    assert_eq!(simple::crack(capture, &words[..100], 4), None);
+
+    // Over TCP.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut impostor = simple::Impostor::new();
+    thread::scope(|scope| {
+      let served = scope.spawn(|| {
+        let (stream, _) = listener.accept().unwrap();
+        srp::serve(&mut impostor, &mut srp::Stream::new(stream))
+      });
+
+      let mut link = srp::Stream::new(TcpStream::connect(addr).unwrap());
+      let mut client = simple::Client::new(email, b"word42");
+      assert_eq!(srp::login(&mut client, &mut link), Ok(true));
+      assert_eq!(served.join().unwrap(), Ok(true));
+    });
+
+    let capture = impostor.capture().unwrap();
+    assert_eq!(capture.salt.len(), 16);
+    assert_eq!(simple::crack(capture, &words, 4), Some("word42"));
+  }
+}
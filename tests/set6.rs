@@ -0,0 +1,248 @@
+#[cfg(test)]
+mod tests {
+  use num_bigint::{BigInt, BigUint};
+  use num_integer::Integer;
+
+  use matasano::dsa::{self, Params, PublicKey, Signature, DSA};
+  use matasano::hash::sha1::Sha1;
+  use matasano::rsa::{self, DecryptionOracle, DecryptionServer, ParityOracle, ParityServer, RSA};
+  use matasano::{from_base64, to_hex};
+
+  #[test]
+  fn challenge39() {
+    let int = |n: u32| BigUint::from(n);
+
+    assert_eq!(rsa::invmod(&int(17), &int(3120)), Some(int(2753)));
+    assert_eq!(rsa::invmod(&int(6), &int(3120)), None);
+    let (g, x, y) = rsa::egcd(&BigInt::from(240), &BigInt::from(46));
+    assert_eq!(g, BigInt::from(2));
+    assert_eq!(x * 240 + y * 46, BigInt::from(2));
+
+    assert!(rsa::is_probable_prime(&int(2), 20));
+    assert!(rsa::is_probable_prime(&int(7919), 20));
+    assert!(!rsa::is_probable_prime(&int(561), 20));
+    assert!(!rsa::is_probable_prime(&int(7917), 20));
+    assert_eq!(rsa::gen_prime(64).unwrap().bits(), 64);
+    assert_eq!(rsa::gen_prime(2), Ok(int(3)));
+    assert!(rsa::gen_prime(1).is_err());
+    assert!(rsa::gen_prime(0).is_err());
+
+    let hex = "0042cafe";
+    let m = rsa::int_from_hex(hex).unwrap();
+    assert_eq!(m, int(0x42cafe));
+    assert_eq!(rsa::int_to_hex(&m), "42cafe");
+    assert_eq!(rsa::from_int(&rsa::to_int(b"hi there")), b"hi there");
+
+    for e in &[3, 65537] {
+      let key = RSA::new(512, *e).unwrap();
+      assert_eq!(key.public().n.bits(), 512);
+      assert_eq!(key.public().e, int(*e));
+
+      let m = int(42);
+      assert_eq!(key.decrypt(&key.encrypt(&m)), m);
+      let m = rsa::to_int(b"attack at dawn");
+      let c = key.public().encrypt(&m);
+      assert_ne!(c, m);
+      assert_eq!(rsa::from_int(&key.decrypt(&c)), b"attack at dawn");
+    }
+
+    assert!(RSA::new(512, 4).is_err());
+  }
+
+  #[test]
+  fn challenge40() {
+    let int = |n: u32| BigUint::from(n);
+
+    let moduli = [int(3), int(5), int(7)];
+    assert_eq!(rsa::crt(&[int(2), int(3), int(2)], &moduli), Some(int(23)));
+    assert_eq!(rsa::crt(&[int(1), int(1)], &[int(4), int(6)]), None);
+    assert_eq!(rsa::nth_root(&int(1000), 3), int(10));
+    assert_eq!(rsa::nth_root(&int(999), 3), int(9));
+    assert_eq!(rsa::exact_nth_root(&int(1000), 3), Some(int(10)));
+    assert_eq!(rsa::exact_nth_root(&int(1001), 3), None);
+
+    let m = rsa::to_int(b"Cooking MC's like a pound of bacon, and then some more bacon");
+    let captures: Vec<(rsa::PublicKey, BigUint)> = (0..3)
+      .map(|_| {
+        let key = RSA::new(512, 3).unwrap();
+        (key.public().clone(), key.encrypt(&m))
+      })
+      .collect();
+    assert_eq!(rsa::broadcast_attack(&captures), Ok(m));
+    assert!(rsa::broadcast_attack(&captures[..2]).is_err());
+  }
+
+  #[test]
+  fn challenge41() {
+    let key = RSA::new(512, 65537).unwrap();
+    let m = rsa::to_int(b"{\"time\": 1356304276, \"social\": \"555-55-5555\"}");
+    let c = key.encrypt(&m);
+
+    let server = DecryptionServer::new(key);
+    assert_eq!(server.decrypt(&c), Ok(m.clone()));
+    assert!(server.decrypt(&c).is_err());
+
+    assert_eq!(rsa::blinding_attack(&server, &c), Ok(m));
+  }
+
+  #[test]
+  fn challenge42() {
+    let key = RSA::new(1024, 3).unwrap();
+    let message = b"hi mom";
+
+    let signature = key.sign(message).unwrap();
+    assert!(key.public().verify(message, &signature, false));
+    assert!(key.public().verify(message, &signature, true));
+    assert!(!key.public().verify(b"hi dad", &signature, false));
+
+    let forged = rsa::forge_signature(key.public(), message).unwrap();
+    assert_ne!(forged, signature);
+    assert!(key.public().verify(message, &forged, false));
+    assert!(!key.public().verify(message, &forged, true));
+
+    let key = RSA::new(1024, 65537).unwrap();
+    assert!(rsa::forge_signature(key.public(), message).is_err());
+  }
+
+  #[test]
+  fn challenge43() {
+    let params = Params::default();
+    let key = DSA::new(&params);
+    let signature = key.sign(b"hi mom").unwrap();
+    assert!(key.public().verify(b"hi mom", &signature));
+    assert!(!key.public().verify(b"hi dad", &signature));
+
+    let k = BigUint::from(31337u32);
+    let signature = key.sign_with_nonce(b"hi mom", &k).unwrap();
+    let x = dsa::key_from_nonce(&params, &dsa::hash(b"hi mom"), &signature, &k).unwrap();
+    assert_eq!(params.g.modpow(&x, &params.p), key.public().y);
+
+    let message = b"For those that envy a MC it can be hazardous to your health\nSo be friendly, a matter of life and death, just like a etch-a-sketch\n";
+    let h = dsa::hash(message);
+    assert_eq!(
+      rsa::int_to_hex(&h),
+      "d2d0714f014a9784047eaeccf956520045c45265"
+    );
+
+    let y = BigUint::parse_bytes(b"84ad4719d044495496a3201c8ff484feb45b962e7302e56a392aee4abab3e4bdebf2955b4736012f21a08084056b19bcd7fee56048e004e44984e2f411788efdc837a0d2e5abb7b555039fd243ac01f0fb2ed1dec568280ce678e931868d23eb095fde9d3779191b8c0299d6e07bbb283e6633451e535c45513b2d33c99ea17", 16).unwrap();
+    let public = PublicKey::new(&params, &y);
+    let signature = Signature {
+      r: "548099063082341131477253921760299949438196259240"
+        .parse()
+        .unwrap(),
+      s: "857042759984254168557880549501802188789837994940"
+        .parse()
+        .unwrap(),
+    };
+    assert!(public.verify(message, &signature));
+
+    let x = dsa::brute_force_key(&public, &h, &signature, 1 << 16).unwrap();
+    assert_eq!(
+      to_hex(&Sha1::digest(x.to_str_radix(16).as_bytes())),
+      "0954edd5e0afe5542a4adf012611a91912a3ec16"
+    );
+  }
+
+  #[test]
+  fn challenge44() {
+    let params = Params::default();
+    let key = DSA::new(&params);
+
+    // A batch where a few signatures reuse a nonce, as a careless signer would produce.
+    let reused = BigUint::from(0xdeadbeefu32);
+    let signed: Vec<(BigUint, Signature)> = (0..10)
+      .map(|i| {
+        let message = format!("message number {}", i);
+        let signature = if i % 4 == 1 {
+          key.sign_with_nonce(message.as_bytes(), &reused).unwrap()
+        } else {
+          key.sign(message.as_bytes()).unwrap()
+        };
+        (dsa::hash(message.as_bytes()), signature)
+      })
+      .collect();
+
+    let x = dsa::find_repeated_nonce(key.public(), &signed).unwrap();
+    assert_eq!(params.g.modpow(&x, &params.p), key.public().y);
+
+    let reused_r = signed[1].1.r.clone();
+    let fresh: Vec<(BigUint, Signature)> = signed
+      .into_iter()
+      .filter(|(_, sig)| sig.r != reused_r)
+      .collect();
+    assert_eq!(fresh.len(), 7);
+    assert_eq!(dsa::find_repeated_nonce(key.public(), &fresh), None);
+  }
+
+  #[test]
+  fn challenge45() {
+    let params = Params::default();
+    let key = DSA::new(&params);
+
+    // With g = 0, every signature has r = 0, and r = 0 verifies for anything.
+    let zero = Params {
+      g: BigUint::from(0u32),
+      ..params.clone()
+    };
+    let sloppy = DSA::new(&zero).with_validation(false);
+    let signature = sloppy.sign(b"Hello, world").unwrap();
+    assert_eq!(signature.r, BigUint::from(0u32));
+    assert!(sloppy.public().verify(b"Hello, world", &signature));
+    assert!(sloppy.public().verify(b"Goodbye, world", &signature));
+
+    let strict = DSA::new(&zero);
+    assert!(strict.sign(b"Hello, world").is_err());
+    let public = sloppy.public().clone().with_validation(true);
+    assert!(!public.verify(b"Goodbye, world", &signature));
+
+    // With g = p + 1, a magic signature made from the public key alone verifies for anything.
+    let one = Params {
+      g: &params.p + 1u32,
+      ..params
+    };
+    let public = PublicKey::new(&one, &key.public().y).with_validation(false);
+    let signature = dsa::magic_signature(&public).unwrap();
+    assert!(public.verify(b"Hello, world", &signature));
+    assert!(public.verify(b"Goodbye, world", &signature));
+
+    let public = public.with_validation(true);
+    assert_eq!(public.params().check(), Err("g out of range"));
+    assert!(!public.verify(b"Hello, world", &signature));
+    assert!(key.public().params().check().is_ok());
+
+    // Tampering with a validated key's params means building a new key, which checks them again.
+    let mut tampered = key.public().params().clone();
+    tampered.g = &tampered.p + 1u32;
+    let public = PublicKey::new(&tampered, &key.public().y);
+    let signature = dsa::magic_signature(&public).unwrap();
+    assert!(!public.verify(b"Hello, world", &signature));
+    assert!(!key.public().verify(b"Hello, world", &signature));
+  }
+
+  #[test]
+  fn challenge46() {
+    let key = RSA::new(1024, 65537).unwrap();
+    let public = key.public().clone();
+    let server = ParityServer::new(key);
+
+    let plain = from_base64("VGhhdCdzIHdoeSBJIGZvdW5kIHlvdSBkb24ndCBwbGF5IGFyb3VuZCB3aXRoIHRoZSBGdW5reSBDb2xkIE1lZGluYQ==").unwrap();
+    let m = rsa::to_int(&plain);
+    let c = public.encrypt(&m);
+    assert_eq!(server.is_even(&c), m.is_even());
+
+    let mut frames = Vec::<Vec<u8>>::new();
+    let mut show = |bound: &BigUint| frames.push(rsa::from_int(bound));
+    let recovered = rsa::parity_attack(&server, &c, Some(&mut show));
+    assert_eq!(rsa::from_int(&recovered), plain);
+    assert_eq!(frames.len(), 1024);
+    assert_eq!(frames.last().unwrap(), &plain);
+
+    // The bounds end up tight at the very edges of the range as well.
+    let key = RSA::new(256, 65537).unwrap();
+    let public = key.public().clone();
+    let server = ParityServer::new(key);
+    for m in &[BigUint::from(1u32), &public.n - 1u32, &public.n - 2u32] {
+      assert_eq!(rsa::parity_attack(&server, &public.encrypt(m), None), *m);
+    }
+  }
+}